@@ -56,17 +56,17 @@ pub fn state_fav_music_genre() -> MoveNode {
             let my_favorite_music_genre = source.fav_music;
             // this is just a placeholder, it's not important for the demonstration
             let location = source.clone();
-            let others_model = state.get_speaker_state_mut(!speaker).character.1.as_mut();
-            others_model.insert_evidence(my_favorite_music_genre.into(), Evidence {
+            let others_mind = state.get_speaker_state_mut(!speaker).character.1.as_mut();
+            others_mind.get_model_mut(&source).insert_evidence(my_favorite_music_genre.into(), Evidence {
                 data: (),
                 kind: EvidenceKind::Statement {
                     source,
                     location,
                 },
                 strength: 100.0,
+                weight: 1.0,
+                origin: None,
             });
-            others_model.recompute_total_strengths();
-            others_model.recompute_strongest();
         }),
         formatter: Box::new(|_, _, parts| {
             format!("My favorite genre of music is {}.", parts[0])
@@ -20,7 +20,7 @@ impl DialogTrait for DialogTypes {
 
     type ExpanderNode = dialog::ExpanderNode;
 
-    type Character = (Arc<Character>, Box<MentalModel<ontology::KnowledgeTypes>>); 
+    type Character = (Arc<Character>, Box<Mind<ontology::KnowledgeTypes>>); 
 }
 
 type MoveNode = cercopes_dialog::MoveNode<DialogTypes>;
@@ -57,11 +57,11 @@ fn print_strongest_beliefs(
     conversation: &ConversationState,
     facets: &[ontology::TestFacet],
 ) {
-    let (ref person0, ref mental_model01) = conversation.person0.character;
-    let (ref person1, ref mental_model10) = conversation.person1.character;
+    let (ref person0, ref mind0) = conversation.person0.character;
+    let (ref person1, ref mind1) = conversation.person1.character;
     println!("\n{}'s beliefs about {}:", person0.name.first, person1.name.first);
     for facet in facets {
-        let belief = if let Some(belief) = mental_model01.get_strongest_belief(facet) {
+        let belief = if let Some(belief) = mind0.get_strongest_belief(person1, facet) {
             belief.to_string()
         } else {
             "none held".to_string()
@@ -70,7 +70,7 @@ fn print_strongest_beliefs(
     }
     println!("\n{}'s beliefs about {}:", person1.name.first, person0.name.first);
     for facet in facets {
-        let belief = if let Some(belief) = mental_model10.get_strongest_belief(facet) {
+        let belief = if let Some(belief) = mind1.get_strongest_belief(person0, facet) {
             belief.to_string()
         } else {
             "none held".to_string()
@@ -83,8 +83,8 @@ fn bench(
     manager: &DialogManager<DialogTypes>,
     initiator: Arc<Character>,
     recipient: Arc<Character>,
-    ir_model: &Box<MentalModel<ontology::KnowledgeTypes>>,
-    ri_model: &Box<MentalModel<ontology::KnowledgeTypes>>,
+    initiator_mind: &Box<Mind<ontology::KnowledgeTypes>>,
+    recipient_mind: &Box<Mind<ontology::KnowledgeTypes>>,
     lull: rand::distributions::Bernoulli,
 ) {
     let timeout = std::time::Duration::new(10, 0);
@@ -96,8 +96,8 @@ fn bench(
             let mut conversation = manager.new_conversation(
                 Speaker::Person0,
                 lull,
-                (initiator.clone(), ir_model.clone()),
-                (recipient.clone(), ri_model.clone()),
+                (initiator.clone(), initiator_mind.clone()),
+                (recipient.clone(), recipient_mind.clone()),
             );
             finish_conversation(&manager, &mut conversation);
         }
@@ -117,15 +117,15 @@ fn rust_test() {
     );
     let initiator = Arc::new(rng.gen::<Character>());
     let recipient = Arc::new(rng.gen::<Character>());
-    let initiator_model = Box::new(MentalModel::new(initiator.clone(), recipient.clone()));
-    let recipient_model = Box::new(MentalModel::new(recipient.clone(), initiator.clone()));
+    let initiator_mind = Box::new(Mind::new(initiator.clone()));
+    let recipient_mind = Box::new(Mind::new(recipient.clone()));
     println!("{initiator}");
     println!("{recipient}");
     let mut conversation = manager.new_conversation(
         Speaker::Person0,
         rand::distributions::Bernoulli::new(0.2).unwrap(),
-        (initiator.clone(), initiator_model.clone()),
-        (recipient.clone(), recipient_model.clone()),
+        (initiator.clone(), initiator_mind.clone()),
+        (recipient.clone(), recipient_mind.clone()),
     );
     finish_conversation(&manager, &mut conversation);
     print_conversation(&conversation);
@@ -136,8 +136,8 @@ fn rust_test() {
         &manager,
         initiator,
         recipient,
-        &initiator_model,
-        &recipient_model,
+        &initiator_mind,
+        &recipient_mind,
         rand::distributions::Bernoulli::new(0.5).unwrap(),
     );
 }
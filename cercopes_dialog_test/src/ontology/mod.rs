@@ -9,6 +9,10 @@ pub enum TestFacet {
 }
 
 impl Entity<KnowledgeTypes> for Arc<Character> {
+    fn id(&self) -> Arc<Character> {
+        self.clone()
+    }
+
     fn relevant_facets(&self) -> Vec<TestFacet> {
         [
             TestFacet::FavMusicGenre,
@@ -50,11 +54,11 @@ impl FacetValue<KnowledgeTypes> for TestFacetValue {
 
     fn try_mutate<R: Rng>(
         &self,
-        _model: &EvidenceModel<KnowledgeTypes>,
-        _evidence: &Evidence<KnowledgeTypes>,
-        _rng: &mut R,
+        model: &EvidenceModel<KnowledgeTypes>,
+        evidence: &Evidence<KnowledgeTypes>,
+        rng: &mut R,
     ) -> Option<Self> {
-        unimplemented!()
+        RandomValue { chance: 0.01 }.try_mutate(self, model, evidence, rng)
     }
 }
 
@@ -68,5 +72,9 @@ impl KnowledgeTrait for KnowledgeTypes {
 
     type Entity = Arc<Character>;
 
+    type EntityId = Arc<Character>;
+
     type Data = ();
+
+    type CustomKind = ();
 }
\ No newline at end of file
@@ -124,8 +124,6 @@ local dialog_manager = DialogManager(
                     },
                     strength = 100,
                 }
-                other_of_source:recompute_total_strengths()
-                other_of_source:recompute_strongest()
             end,
             formatter = function(state, parts)
                 return "My favorite genre of music is " .. table.concat(parts) .. "."
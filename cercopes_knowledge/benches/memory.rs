@@ -0,0 +1,256 @@
+//! Measures the memory that the minds of a community take up after a round of gossip, when
+//! evidence refers to entities by cloning them and when it refers to them by [`EntityId`].
+//!
+//! Run with `cargo bench -p cercopes_knowledge --bench memory`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use cercopes_knowledge::*;
+use rand::prelude::*;
+
+const PEOPLE: u32 = 200;
+const OBSERVATIONS: usize = 2_000;
+const TELLINGS: usize = 20_000;
+
+/// The global allocator, which keeps count of the bytes currently allocated.
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Trait {
+    Hair,
+    Job,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Value {
+    Black,
+    Red,
+    Farmer,
+    Smith,
+}
+
+impl<K: KnowledgeTrait<FacetValue = Value>> Facet<K> for Trait {
+    fn initial_values(&self) -> Vec<Value> {
+        Vec::new()
+    }
+}
+
+impl<K: KnowledgeTrait<Facet = Trait, FacetValue = Value>> FacetValue<K> for Value {
+    fn facet(&self) -> Trait {
+        match self {
+            Value::Black | Value::Red => Trait::Hair,
+            Value::Farmer | Value::Smith => Trait::Job,
+        }
+    }
+
+    fn try_mutate<R: Rng>(&self, _: &EvidenceModel<K>, _: &Evidence<K>, _: &mut R) -> Option<Self> {
+        None
+    }
+}
+
+/// A person who, like many game entities, owns heap data and so is expensive to clone.
+#[derive(Debug, Clone)]
+struct Person {
+    id: EntityId,
+    traits: Vec<Value>,
+}
+
+impl Person {
+    fn new(id: EntityId, rng: &mut StdRng) -> Self {
+        Person {
+            id,
+            traits: vec![
+                *[Value::Black, Value::Red].choose(rng).unwrap(),
+                *[Value::Farmer, Value::Smith].choose(rng).unwrap(),
+            ],
+        }
+    }
+
+    fn facets(&self) -> Vec<Trait> {
+        self.traits.iter().map(FacetValue::<ById>::facet).collect()
+    }
+
+    fn truth(&self, facet: &Trait) -> Option<Value> {
+        self.traits.iter().copied().find(|value| FacetValue::<ById>::facet(value) == *facet)
+    }
+}
+
+impl PartialEq for Person {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Person {}
+
+impl Hash for Person {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/// Evidence refers to people by cloning them.
+#[derive(Debug)]
+struct ByValue;
+
+impl KnowledgeTrait for ByValue {
+    type Facet = Trait;
+
+    type FacetValue = Value;
+
+    type Entity = Person;
+
+    type EntityId = Person;
+
+    type Data = ();
+
+    type CustomKind = ();
+}
+
+impl Entity<ByValue> for Person {
+    fn id(&self) -> Person {
+        self.clone()
+    }
+
+    fn relevant_facets(&self) -> Vec<Trait> {
+        self.facets()
+    }
+
+    fn facet_truth(&self, facet: &Trait) -> Option<Value> {
+        self.truth(facet)
+    }
+}
+
+/// Evidence refers to people by their IDs.
+#[derive(Debug)]
+struct ById;
+
+impl KnowledgeTrait for ById {
+    type Facet = Trait;
+
+    type FacetValue = Value;
+
+    type Entity = Person;
+
+    type EntityId = EntityId;
+
+    type Data = ();
+
+    type CustomKind = ();
+}
+
+impl Entity<ById> for Person {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn relevant_facets(&self) -> Vec<Trait> {
+        self.facets()
+    }
+
+    fn facet_truth(&self, facet: &Trait) -> Option<Value> {
+        self.truth(facet)
+    }
+}
+
+/// Have everyone observe and gossip about each other, and get their minds.
+fn gossip<K>(people: &EntityRegistry<Person>) -> Vec<Mind<K>>
+where
+    K: KnowledgeTrait<Facet = Trait, FacetValue = Value, Entity = Person, Data = ()>,
+    Person: Entity<K>,
+{
+    let mut rng = StdRng::seed_from_u64(0);
+    let person = |rng: &mut StdRng| people.get(EntityId(rng.gen_range(0..PEOPLE))).unwrap();
+    let mut minds: Vec<_> = people
+        .iter()
+        .map(|(_, person)| Mind::<K>::new(person.clone()))
+        .collect();
+
+    for _ in 0..OBSERVATIONS {
+        let (observer, observed) = (person(&mut rng), person(&mut rng));
+        if observer != observed {
+            let facet = *[Trait::Hair, Trait::Job].choose(&mut rng).unwrap();
+            let observation = Evidence {
+                data: (),
+                kind: EvidenceKind::Observation { location: Entity::<K>::id(observer) },
+                strength: 1.0,
+                weight: 1.0,
+                origin: None,
+            };
+            let mind = &mut minds[observer.id.0 as usize];
+            mind.insert_evidence(observed, observed.truth(&facet).unwrap(), observation);
+        }
+    }
+
+    for _ in 0..TELLINGS {
+        let (speaker, listener, regarding) = (person(&mut rng), person(&mut rng), person(&mut rng));
+        if speaker == listener {
+            continue;
+        }
+        let (speaker, listener) = (speaker.id.0 as usize, listener.id.0 as usize);
+        let (speaker_mind, listener_mind) = if speaker < listener {
+            let (left, right) = minds.split_at_mut(listener);
+            (&mut left[speaker], &mut right[0])
+        } else {
+            let (left, right) = minds.split_at_mut(speaker);
+            (&mut right[0], &mut left[listener])
+        };
+        let claim = Claim::Strongest(*[Trait::Hair, Trait::Job].choose(&mut rng).unwrap());
+        let location = Entity::<K>::id(listener_mind.holder());
+        speaker_mind.tell(listener_mind, regarding, claim, location, (), 1.0);
+    }
+
+    minds
+}
+
+/// Get the bytes that the minds from [`gossip`] take up, including their evidence.
+fn measure<K>(people: &EntityRegistry<Person>) -> usize
+where
+    K: KnowledgeTrait<Facet = Trait, FacetValue = Value, Entity = Person, Data = ()>,
+    Person: Entity<K>,
+{
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let minds = gossip::<K>(people);
+    let bytes = ALLOCATED.load(Ordering::Relaxed) - before;
+    drop(minds);
+    bytes
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut people = EntityRegistry::new();
+    for _ in 0..PEOPLE {
+        people.insert_with(|id| Person::new(id, &mut rng));
+    }
+
+    println!("{PEOPLE} minds after {OBSERVATIONS} observations and {TELLINGS} tellings:");
+    let by_value = measure::<ByValue>(&people);
+    println!(
+        "  by value: {by_value} bytes ({} bytes per piece of evidence)",
+        std::mem::size_of::<Evidence<ByValue>>(),
+    );
+    let by_id = measure::<ById>(&people);
+    println!(
+        "  by ID:    {by_id} bytes ({} bytes per piece of evidence)",
+        std::mem::size_of::<Evidence<ById>>(),
+    );
+    println!("  saved:    {:.1}%", 100.0 * (1.0 - by_id as f64 / by_value as f64));
+}
@@ -0,0 +1,263 @@
+use super::*;
+
+/// Many models (or [minds](Mind)) to update at once.
+///
+/// With the `send_sync` feature, every operation is split across threads. Each model is updated
+/// on its own, and [mutation](Batch::mutate) gives every model its own RNG seeded from the batch
+/// seed and the model's position in the batch, so the results are the same with or without the
+/// feature, and no matter how many threads are used.
+pub struct Batch<'a, T> {
+    items: Vec<&'a mut T>,
+}
+
+impl<'a, T: MaybeSend> Batch<'a, T> {
+    /// Create a batch of models, in order.
+    pub fn new(items: impl IntoIterator<Item = &'a mut T>) -> Self {
+        Batch {
+            items: items.into_iter().collect(),
+        }
+    }
+
+    /// Get the number of models in the batch.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Check whether the batch has no models.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Run `f` on every model with its position in the batch.
+    #[cfg(feature = "send_sync")]
+    pub fn for_each<F>(&mut self, f: F)
+    where
+        F: Fn(usize, &mut T) + MaybeSync,
+    {
+        let threads = std::thread::available_parallelism().map_or(1, usize::from);
+        let chunk_size = self.items.len().div_ceil(threads).max(1);
+        let f = &f;
+        std::thread::scope(|scope| {
+            for (chunk_i, chunk) in self.items.chunks_mut(chunk_size).enumerate() {
+                scope.spawn(move || {
+                    for (i, item) in chunk.iter_mut().enumerate() {
+                        f(chunk_i * chunk_size + i, item);
+                    }
+                });
+            }
+        });
+    }
+
+    /// Run `f` on every model with its position in the batch.
+    #[cfg(not(feature = "send_sync"))]
+    pub fn for_each<F>(&mut self, f: F)
+    where
+        F: Fn(usize, &mut T) + MaybeSync,
+    {
+        for (i, item) in self.items.iter_mut().enumerate() {
+            f(i, item);
+        }
+    }
+
+    /// Run `f` on every model with an RNG of its own.
+    ///
+    /// The RNG of the model at position `i` is seeded with [`batch_seed(seed, i)`](batch_seed).
+    pub fn for_each_seeded<R, F>(&mut self, seed: u64, f: F)
+    where
+        R: Rng + SeedableRng,
+        F: Fn(&mut T, &mut R) + MaybeSync,
+    {
+        self.for_each(|i, item| f(item, &mut R::seed_from_u64(batch_seed(seed, i))));
+    }
+}
+
+/// Get the seed of the RNG for the model at position `i` of a batch.
+pub fn batch_seed(seed: u64, i: usize) -> u64 {
+    // splitmix64, so that neighbouring models get unrelated seeds
+    let mut z = seed.wrapping_add((i as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+impl<K: KnowledgeTrait> Batch<'_, EvidenceModel<K>>
+where
+    EvidenceModel<K>: MaybeSend,
+{
+    /// Mutate every model with an RNG of its own.
+    ///
+    /// See [`EvidenceModel::mutate`] and [`Batch::for_each_seeded`].
+    pub fn mutate<R: Rng + SeedableRng>(&mut self, seed: u64) {
+        self.for_each_seeded(seed, |model, rng: &mut R| model.mutate(rng));
+    }
+
+    /// Update all of the true facet values in every model.
+    ///
+    /// See [`EvidenceModel::update_truths`].
+    pub fn update_truths(&mut self) {
+        self.for_each(|_, model| model.update_truths());
+    }
+
+    /// Update all of the true facet values in every model, returning the first error.
+    ///
+    /// Every model is updated even if another fails. See [`EvidenceModel::try_update_truths`].
+    pub fn try_update_truths(&mut self) -> Result<(), KnowledgeError> {
+        let errors = std::sync::Mutex::new(Vec::new());
+        self.for_each(|i, model| {
+            if let Err(err) = model.try_update_truths() {
+                errors.lock().unwrap().push((i, err));
+            }
+        });
+        first_error(errors)
+    }
+
+    /// Recompute all of the total strengths in every model.
+    ///
+    /// See [`EvidenceModel::recompute_total_strengths`].
+    pub fn recompute_total_strengths(&mut self) {
+        self.for_each(|_, model| model.recompute_total_strengths());
+    }
+
+    /// Recompute all of the strongest beliefs in every model.
+    ///
+    /// See [`EvidenceModel::recompute_strongest`].
+    pub fn recompute_strongest(&mut self) {
+        self.for_each(|_, model| model.recompute_strongest());
+    }
+}
+
+impl<K: KnowledgeTrait> Batch<'_, Mind<K>>
+where
+    K::Entity: Eq + Hash,
+    Mind<K>: MaybeSend,
+{
+    /// Mutate every mind with an RNG of its own.
+    ///
+    /// Each mind mutates its models in iteration order, so the results are only reproducible
+    /// between runs with the `deterministic` feature. See [`Mind::mutate`].
+    pub fn mutate<R: Rng + SeedableRng>(&mut self, seed: u64) {
+        self.for_each_seeded(seed, |mind, rng: &mut R| mind.mutate(rng));
+    }
+
+    /// Update all of the true facet values in every mind.
+    ///
+    /// See [`Mind::update_truths`].
+    pub fn update_truths(&mut self) {
+        self.for_each(|_, mind| mind.update_truths());
+    }
+
+    /// Update all of the true facet values in every mind, returning the first error.
+    ///
+    /// Every mind is updated even if another fails. See [`Mind::try_update_truths`].
+    pub fn try_update_truths(&mut self) -> Result<(), KnowledgeError> {
+        let errors = std::sync::Mutex::new(Vec::new());
+        self.for_each(|i, mind| {
+            if let Err(err) = mind.try_update_truths() {
+                errors.lock().unwrap().push((i, err));
+            }
+        });
+        first_error(errors)
+    }
+
+    /// Recompute all of the total strengths in every mind.
+    ///
+    /// See [`Mind::recompute_total_strengths`].
+    pub fn recompute_total_strengths(&mut self) {
+        self.for_each(|_, mind| mind.recompute_total_strengths());
+    }
+
+    /// Recompute all of the total strengths in every mind, weighted by trust.
+    ///
+    /// See [`Mind::recompute_trusted_strengths`].
+    pub fn recompute_trusted_strengths(&mut self) {
+        self.for_each(|_, mind| mind.recompute_trusted_strengths());
+    }
+
+    /// Recompute all of the strongest beliefs in every mind.
+    ///
+    /// See [`Mind::recompute_strongest`].
+    pub fn recompute_strongest(&mut self) {
+        self.for_each(|_, mind| mind.recompute_strongest());
+    }
+}
+
+/// Get the error of the earliest model in a batch that failed, if any did.
+fn first_error(
+    errors: std::sync::Mutex<Vec<(usize, KnowledgeError)>>,
+) -> Result<(), KnowledgeError> {
+    match errors.into_inner().unwrap().into_iter().min_by_key(|(i, _)| *i) {
+        Some((_, err)) => Err(err),
+        None => Ok(()),
+    }
+}
+
+impl<K: KnowledgeTrait> Mind<K>
+where
+    K::Entity: Eq + Hash,
+    EvidenceModel<K>: MaybeSend,
+{
+    /// Get a [batch](Batch) of every evidence model of this mind, including nested models, like
+    /// [`Mind::mutate`].
+    ///
+    /// Relation models and the reflexive model aren't included.
+    pub fn batch(&mut self) -> Batch<'_, EvidenceModel<K>> {
+        let nested = self.nested.values_mut().flat_map(KnowledgeMap::values_mut);
+        Batch::new(self.models.values_mut().chain(nested))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    fn models() -> Vec<EvidenceModel<TestTypes>> {
+        let dave = TestEntity::new("dave", TestValue::Red, TestValue::Smith);
+        [bob(), carol(), dave]
+            .into_iter()
+            .map(|regarding| {
+                let mut model = EvidenceModel::new(alice(), regarding);
+                for strength in 1..=8 {
+                    let kind = EvidenceKind::Observation { location: alice().id() };
+                    model.insert_evidence(TestValue::Red, evidence(kind, strength as f32));
+                }
+                model
+            })
+            .collect()
+    }
+
+    fn red_strengths(models: &[EvidenceModel<TestTypes>]) -> Vec<f32> {
+        models
+            .iter()
+            .map(|model| model.get_value(&TestValue::Red).unwrap().total_strength())
+            .collect()
+    }
+
+    #[test]
+    fn batches_mutate_deterministically() {
+        let mut batched = models();
+        let mut batch = Batch::new(&mut batched);
+        assert_eq!(batch.len(), 3);
+        batch.mutate::<StdRng>(5);
+        batch.recompute_total_strengths();
+        batch.recompute_strongest();
+        batch.update_truths();
+        assert!(batch.try_update_truths().is_ok());
+
+        let mut sequential = models();
+        for (i, model) in sequential.iter_mut().enumerate() {
+            model.mutate(&mut StdRng::seed_from_u64(batch_seed(5, i)));
+        }
+        assert_eq!(red_strengths(&batched), red_strengths(&sequential));
+        assert!(red_strengths(&batched).iter().any(|strength| *strength < 36.0));
+        assert_ne!(batch_seed(5, 0), batch_seed(5, 1));
+    }
+
+    #[test]
+    fn minds_batch_nested_models() {
+        let mut mind = Mind::<TestTypes>::new(alice());
+        mind.get_model_mut(&bob());
+        mind.get_nested_mut(&bob(), &carol());
+        assert_eq!(mind.batch().len(), 2);
+    }
+}
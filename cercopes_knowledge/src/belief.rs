@@ -0,0 +1,196 @@
+use super::*;
+
+impl<K: KnowledgeTrait> FacetData<K> {
+    /// Get the total positive strength across all of the values of this facet.
+    ///
+    /// Values with zero or negative total strength lend no support to any belief, in the same way
+    /// that they can never be the [strongest](FacetData::strongest) belief.
+    pub fn total_support(&self) -> f32 {
+        self.values
+            .values()
+            .map(|data| data.total_strength.max(0.0))
+            .sum()
+    }
+
+    /// Get the probability the holder assigns to a particular value.
+    ///
+    /// This is the value's share of the [total support](FacetData::total_support). Returns `0.0`
+    /// if the value is unknown or if no value has any positive support.
+    pub fn probability(&self, value: &K::FacetValue) -> f32 {
+        let support = self.total_support();
+        if support <= 0.0 {
+            return 0.0;
+        }
+
+        self.values
+            .get(value)
+            .map_or(0.0, |data| data.total_strength.max(0.0) / support)
+    }
+
+    /// Get the normalized belief distribution over the values of this facet.
+    ///
+    /// Only values with positive support are included, and they are sorted from most to least
+    /// probable. The probabilities sum to `1.0`, unless no value has any positive support, in which
+    /// case the distribution is empty.
+    pub fn distribution(&self) -> Vec<(&K::FacetValue, f32)> {
+        let support = self.total_support();
+        if support <= 0.0 {
+            return Vec::new();
+        }
+
+        let mut distribution: Vec<_> = self.values
+            .iter()
+            .filter(|(_, data)| data.total_strength > 0.0)
+            .map(|(value, data)| (value, data.total_strength / support))
+            .collect();
+        distribution.sort_by(|(_, l), (_, r)| r.total_cmp(l));
+        distribution
+    }
+
+    /// Get how confident the holder is in their strongest belief.
+    ///
+    /// This is the margin between the probabilities of the two most probable values, so it is
+    /// `1.0` when only one value has any support and close to `0.0` for a "51/49" split. Returns
+    /// `0.0` when no value has any positive support.
+    pub fn confidence(&self) -> f32 {
+        match self.distribution().as_slice() {
+            [] => 0.0,
+            [_] => 1.0,
+            [(_, first), (_, second), ..] => first - second,
+        }
+    }
+
+    /// Get how uncertain the holder is about this facet.
+    ///
+    /// This is the Shannon entropy of the [distribution](FacetData::distribution), normalized by
+    /// the number of known values so that it lies in `[0.0, 1.0]`. Returns `1.0` (complete
+    /// uncertainty) when no value has any positive support.
+    pub fn uncertainty(&self) -> f32 {
+        let distribution = self.distribution();
+        if distribution.is_empty() {
+            return 1.0;
+        } else if self.values.len() <= 1 {
+            return 0.0;
+        }
+
+        let entropy: f32 = distribution
+            .into_iter()
+            .map(|(_, p)| -p * p.ln())
+            .sum();
+        (entropy / (self.values.len() as f32).ln()).clamp(0.0, 1.0)
+    }
+}
+
+impl<K: KnowledgeTrait> EvidenceModel<K> {
+    /// Get the normalized belief distribution for a facet.
+    ///
+    /// See [`FacetData::distribution`]. Empty if the facet isn't in the model.
+    pub fn get_distribution(&self, facet: &K::Facet) -> Vec<(&K::FacetValue, f32)> {
+        self.facets
+            .get(facet)
+            .map_or_else(Vec::new, FacetData::distribution)
+    }
+
+    /// Get how confident the holder is in their strongest belief of a facet.
+    ///
+    /// See [`FacetData::confidence`]. `0.0` if the facet isn't in the model.
+    pub fn get_confidence(&self, facet: &K::Facet) -> f32 {
+        self.facets.get(facet).map_or(0.0, FacetData::confidence)
+    }
+
+    /// Get how uncertain the holder is about a facet.
+    ///
+    /// See [`FacetData::uncertainty`]. `1.0` if the facet isn't in the model.
+    pub fn get_uncertainty(&self, facet: &K::Facet) -> f32 {
+        self.facets.get(facet).map_or(1.0, FacetData::uncertainty)
+    }
+}
+
+impl<K: KnowledgeTrait> MentalModel<K> {
+    /// Get the normalized belief distribution for a facet.
+    ///
+    /// A [reflexive model](ReflexiveModel) is always certain of its own facets.
+    pub fn get_distribution(&self, facet: &K::Facet) -> Vec<(&K::FacetValue, f32)> {
+        match self {
+            MentalModel::Reflexive(reflexive) => reflexive.facets
+                .get(facet)
+                .map(|value| (value, 1.0))
+                .into_iter()
+                .collect(),
+            MentalModel::Evidence(evidence) => evidence.get_distribution(facet),
+        }
+    }
+
+    /// Get how confident the holder is in their strongest belief of a facet.
+    pub fn get_confidence(&self, facet: &K::Facet) -> f32 {
+        match self {
+            MentalModel::Reflexive(reflexive) => if reflexive.facets.contains_key(facet) {
+                1.0
+            } else {
+                0.0
+            },
+            MentalModel::Evidence(evidence) => evidence.get_confidence(facet),
+        }
+    }
+
+    /// Get how uncertain the holder is about a facet.
+    pub fn get_uncertainty(&self, facet: &K::Facet) -> f32 {
+        match self {
+            MentalModel::Reflexive(reflexive) => if reflexive.facets.contains_key(facet) {
+                0.0
+            } else {
+                1.0
+            },
+            MentalModel::Evidence(evidence) => evidence.get_uncertainty(facet),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    fn hair_model(strengths: &[(TestValue, f32)]) -> EvidenceModel<TestTypes> {
+        let mut model = EvidenceModel::new(alice(), bob());
+        for &(value, strength) in strengths {
+            model.insert_evidence(value, evidence(EvidenceKind::Implantation, strength));
+        }
+        model.recompute_total_strengths();
+        model.recompute_strongest();
+        model
+    }
+
+    #[test]
+    fn distribution_is_normalized_and_sorted() {
+        let model = hair_model(&[
+            (TestValue::Red, 1.0),
+            (TestValue::Green, 3.0),
+            (TestValue::Blue, -2.0),
+        ]);
+        let distribution = model.get_distribution(&TestFacet::HairColor);
+        assert_eq!(distribution, vec![(&TestValue::Green, 0.75), (&TestValue::Red, 0.25)]);
+        assert!((model.get_confidence(&TestFacet::HairColor) - 0.5).abs() < 1e-6);
+        let facet = &model.facets[&TestFacet::HairColor];
+        assert_eq!(facet.probability(&TestValue::Blue), 0.0);
+    }
+
+    #[test]
+    fn close_split_is_uncertain() {
+        let sure = hair_model(&[(TestValue::Green, 10.0)]);
+        let split = hair_model(&[(TestValue::Green, 5.1), (TestValue::Red, 4.9)]);
+        assert_eq!(sure.get_confidence(&TestFacet::HairColor), 1.0);
+        assert_eq!(sure.get_uncertainty(&TestFacet::HairColor), 0.0);
+        assert!(split.get_confidence(&TestFacet::HairColor) < 0.05);
+        assert!(split.get_uncertainty(&TestFacet::HairColor) > 0.6);
+    }
+
+    #[test]
+    fn no_support_is_well_defined() {
+        let model = hair_model(&[(TestValue::Red, 0.0), (TestValue::Green, -1.0)]);
+        assert!(model.get_distribution(&TestFacet::HairColor).is_empty());
+        assert_eq!(model.get_confidence(&TestFacet::HairColor), 0.0);
+        assert_eq!(model.get_uncertainty(&TestFacet::HairColor), 1.0);
+        assert_eq!(model.facets[&TestFacet::HairColor].probability(&TestValue::Red), 0.0);
+    }
+}
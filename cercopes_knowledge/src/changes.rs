@@ -0,0 +1,268 @@
+use super::*;
+
+/// A change in a holder's strongest belief of a facet of an entity.
+///
+/// These are recorded by [observed](EvidenceModel::set_observing) evidence models, and can be
+/// [drained](EvidenceModel::drain_changes) to react to a holder changing their mind.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K::Facet: Serialize, K::FacetValue: Serialize, K::Entity: Serialize",
+    deserialize = "
+        K::Facet: for<'a> Deserialize<'a>,
+        K::FacetValue: for<'a> Deserialize<'a>,
+        K::Entity: for<'a> Deserialize<'a>,
+    ",
+))]
+pub struct BeliefChange<K: KnowledgeTrait> {
+    /// The entity whose belief changed.
+    pub holder: K::Entity,
+    /// The entity the belief is about.
+    pub regarding: K::Entity,
+    /// The facet whose strongest belief changed.
+    pub facet: K::Facet,
+    /// The strongest belief before the change.
+    pub old: Option<K::FacetValue>,
+    /// The strongest belief after the change.
+    pub new: Option<K::FacetValue>,
+}
+
+impl<K: KnowledgeTrait> Clone for BeliefChange<K> {
+    fn clone(&self) -> Self {
+        Self {
+            holder: self.holder.clone(),
+            regarding: self.regarding.clone(),
+            facet: self.facet.clone(),
+            old: self.old.clone(),
+            new: self.new.clone(),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for BeliefChange<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("BeliefChange")
+            .field("holder", &self.holder)
+            .field("regarding", &self.regarding)
+            .field("facet", &self.facet)
+            .field("old", &self.old)
+            .field("new", &self.new)
+            .finish()
+    }
+}
+
+/// Record a change to the strongest belief of a facet, if there was one and it's being observed.
+fn record<K: KnowledgeTrait>(
+    changes: &mut Option<Vec<BeliefChange<K>>>,
+    holder: &K::Entity,
+    regarding: &K::Entity,
+    facet: &K::Facet,
+    old: Option<Option<K::FacetValue>>,
+    new: &Option<K::FacetValue>,
+) {
+    if let (Some(changes), Some(old)) = (changes, old) {
+        if old != *new {
+            changes.push(BeliefChange {
+                holder: holder.clone(),
+                regarding: regarding.clone(),
+                facet: facet.clone(),
+                old,
+                new: new.clone(),
+            });
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> EvidenceModel<K> {
+    /// Start or stop recording [changes](BeliefChange) to the strongest beliefs of this model.
+    ///
+    /// Stopping discards any changes that haven't been drained yet. Whether the model is observing
+    /// and its undrained changes are saved with it, so a loaded model keeps observing.
+    pub fn set_observing(&mut self, observing: bool) {
+        if !observing {
+            self.changes = None;
+        } else if self.changes.is_none() {
+            self.changes = Some(Vec::new());
+        }
+    }
+
+    /// Check whether changes to the strongest beliefs of this model are being recorded.
+    pub fn is_observing(&self) -> bool {
+        self.changes.is_some()
+    }
+
+    /// Take every recorded [change](BeliefChange) to the strongest beliefs, oldest first.
+    ///
+    /// Changes are recorded by every method of the model that updates the strongest beliefs, such
+    /// as [`EvidenceModel::recompute_strongest`] and [`EvidenceModel::update_facet`]. Changes made
+    /// directly to the [facet data](FacetData) aren't recorded.
+    pub fn drain_changes(&mut self) -> Vec<BeliefChange<K>> {
+        self.changes.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Modify the data of a facet (initializing it if necessary), recording any change to its
+    /// strongest belief.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the facet data must be initialized and the true value of the facet can't be
+    /// found. See [`EvidenceModel::try_update_facet`].
+    pub fn update_facet<F, R>(&mut self, facet: K::Facet, update: F) -> R
+    where
+        F: FnOnce(&mut FacetData<K>) -> R,
+    {
+        or_panic(self.try_update_facet(facet, update))
+    }
+
+    /// Modify the data of a facet (initializing it if necessary), recording any change to its
+    /// strongest belief, or return an error if it must be initialized and the true value of the
+    /// facet can't be found.
+    pub fn try_update_facet<F, R>(&mut self, facet: K::Facet, update: F) -> Result<R, KnowledgeError>
+    where
+        F: FnOnce(&mut FacetData<K>) -> R,
+    {
+        debug_assert!(self.regarding.try_is_facet_relevant(&facet) != Ok(false));
+        let regarding = &self.regarding;
+        let data = match self.facets.entry(facet.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let data = FacetData::try_new(regarding, entry.key())?;
+                entry.insert(data)
+            },
+        };
+        let old = self.changes.is_some().then(|| data.strongest.clone());
+        let result = update(data);
+        record(&mut self.changes, &self.holder, regarding, &facet, old, &data.strongest);
+        Ok(result)
+    }
+
+    /// Modify the data of every facet, recording any changes to their strongest beliefs.
+    pub fn update_facets<F>(&mut self, mut update: F)
+    where
+        F: FnMut(&mut FacetData<K>),
+    {
+        for (facet, data) in self.facets.iter_mut() {
+            let old = self.changes.is_some().then(|| data.strongest.clone());
+            update(data);
+            record(&mut self.changes, &self.holder, &self.regarding, facet, old, &data.strongest);
+        }
+    }
+
+    /// Recompute the total strengths and the strongest belief of a single facet.
+    pub fn recompute_facet(&mut self, facet: K::Facet) {
+        self.update_facet(facet, |data| {
+            data.recompute_total_strengths();
+            data.recompute_strongest();
+        });
+    }
+}
+
+impl<K: KnowledgeTrait> Mind<K>
+where
+    K::Entity: Eq + Hash,
+{
+    /// Start or stop recording [changes](BeliefChange) to the strongest beliefs of every model,
+    /// including nested models and models created later.
+    ///
+    /// Relation models don't record changes. See [`EvidenceModel::set_observing`].
+    pub fn set_observing(&mut self, observing: bool) {
+        self.observing = observing;
+        for model in self.models.values_mut() {
+            model.set_observing(observing);
+        }
+        for model in self.nested_models_mut() {
+            model.set_observing(observing);
+        }
+    }
+
+    /// Take every recorded [change](BeliefChange) to the strongest beliefs of every model,
+    /// including nested models.
+    ///
+    /// The [holder](BeliefChange::holder) of a change to a nested model is the believer. Changes
+    /// are in order per model, but models are in no particular order. See
+    /// [`EvidenceModel::drain_changes`].
+    pub fn drain_changes(&mut self) -> Vec<BeliefChange<K>> {
+        let nested = self.nested.values_mut().flat_map(KnowledgeMap::values_mut);
+        self.models
+            .values_mut()
+            .chain(nested)
+            .flat_map(EvidenceModel::drain_changes)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn flips_are_recorded_when_observing() {
+        let mut model = EvidenceModel::<TestTypes>::new(alice(), bob());
+        model.insert_evidence(TestValue::Red, evidence(EvidenceKind::Implantation, 1.0));
+        model.recompute_total_strengths();
+        model.recompute_strongest();
+        assert!(model.drain_changes().is_empty());
+
+        model.set_observing(true);
+        model.insert_evidence(TestValue::Green, evidence(EvidenceKind::Implantation, 0.5));
+        model.recompute_facet(TestFacet::HairColor);
+        assert!(model.drain_changes().is_empty());
+        model.insert_evidence(TestValue::Green, evidence(EvidenceKind::Implantation, 1.0));
+        model.insert_evidence(TestValue::Smith, evidence(EvidenceKind::Implantation, 1.0));
+        model.recompute_total_strengths();
+        model.recompute_strongest();
+
+        let mut changes = model.drain_changes();
+        changes.sort_by_key(|change| change.facet == TestFacet::Job);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].holder, alice());
+        assert_eq!(changes[0].regarding, bob());
+        assert_eq!(changes[0].old, Some(TestValue::Red));
+        assert_eq!(changes[0].new, Some(TestValue::Green));
+        assert_eq!(changes[1].old, None);
+        assert_eq!(changes[1].new, Some(TestValue::Smith));
+        assert!(model.drain_changes().is_empty());
+    }
+
+    #[test]
+    fn minds_observe_new_models() {
+        let mut speaker = Mind::<TestTypes>::new(alice());
+        let mut listener = Mind::<TestTypes>::new(bob());
+        listener.set_observing(true);
+        speaker.tell(&mut listener, &carol(), Claim::Lie(TestValue::Green), bob().id(), 0.0, 1.0);
+        let mut changes = listener.drain_changes();
+        changes.sort_by_key(|change| change.holder != bob());
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].holder, bob());
+        assert_eq!(changes[0].facet, TestFacet::HairColor);
+        assert_eq!(changes[0].new, Some(TestValue::Green));
+        assert!(speaker.drain_changes().is_empty());
+    }
+
+    #[test]
+    fn nested_and_loaded_models_keep_observing() {
+        let mut speaker = Mind::<TestTypes>::new(alice());
+        let mut listener = Mind::<TestTypes>::new(bob());
+        speaker.set_observing(true);
+        speaker.tell(&mut listener, &carol(), Claim::Lie(TestValue::Green), bob().id(), 0.0, 1.0);
+        let mut changes = speaker.drain_changes();
+        changes.sort_by_key(|change| change.holder != bob());
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].holder, bob());
+        assert_eq!(changes[0].regarding, carol());
+        assert_eq!(changes[0].new, Some(TestValue::Green));
+
+        let mut model = EvidenceModel::<TestTypes>::new(alice(), bob());
+        model.set_observing(true);
+        model.insert_evidence(TestValue::Red, evidence(EvidenceKind::Implantation, 1.0));
+        let json = serde_json::to_string(&model).unwrap();
+        let mut loaded: EvidenceModel<TestTypes> = serde_json::from_str(&json).unwrap();
+        assert!(loaded.is_observing());
+        assert_eq!(loaded.drain_changes().len(), 1);
+        assert_eq!(
+            model.try_update_facet(TestFacet::Job, |data| data.strongest().cloned()).unwrap(),
+            None,
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use super::*;
+
+/// The holders who most strongly believe the same value of a facet.
+pub struct Faction<'a, K: KnowledgeTrait> {
+    /// The value the faction believes.
+    pub value: &'a K::FacetValue,
+    /// Every holder in the faction.
+    pub members: Vec<&'a K::Entity>,
+    /// The faction's share of the holders with a belief about the facet.
+    pub share: f32,
+}
+
+impl<K: KnowledgeTrait> Clone for Faction<'_, K> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value,
+            members: self.members.clone(),
+            share: self.share,
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for Faction<'_, K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("Faction")
+            .field("value", self.value)
+            .field("members", &self.members)
+            .field("share", &self.share)
+            .finish()
+    }
+}
+
+/// What a community of holders collectively believes about a facet of an entity.
+pub struct FacetConsensus<'a, K: KnowledgeTrait> {
+    /// The facet.
+    pub facet: &'a K::Facet,
+    /// The true value of the facet.
+    pub truth: &'a K::FacetValue,
+    /// The holders grouped by their strongest belief, largest faction first.
+    ///
+    /// Holders without any belief about the facet aren't in any faction.
+    pub factions: Vec<Faction<'a, K>>,
+    /// How far the community is from the truth, from `0.0` (every holder is certain of the truth)
+    /// to `1.0` (no holder gives the truth any support).
+    ///
+    /// This is one minus the mean [probability](FacetData::probability) the holders with a belief
+    /// assign to the truth.
+    pub distance_from_truth: f32,
+}
+
+impl<K: KnowledgeTrait> FacetConsensus<'_, K> {
+    /// Get the value most holders most strongly believe.
+    ///
+    /// Returns `None` if the largest factions are tied, since there's no single majority value.
+    pub fn majority(&self) -> Option<&K::FacetValue> {
+        match self.factions.as_slice() {
+            [first, second, ..] if first.members.len() == second.members.len() => None,
+            [first, ..] => Some(first.value),
+            [] => None,
+        }
+    }
+
+    /// Get the share of holders in the largest faction, even if it's tied with another.
+    pub fn agreement(&self) -> f32 {
+        self.factions.first().map_or(0.0, |faction| faction.share)
+    }
+
+    /// Check whether most holders most strongly believe the truth.
+    pub fn majority_is_true(&self) -> bool {
+        self.majority() == Some(self.truth)
+    }
+
+    /// Get the number of holders with a belief about the facet.
+    pub fn holders(&self) -> usize {
+        self.factions.iter().map(|faction| faction.members.len()).sum()
+    }
+
+    /// Get the factions with a share of at least `threshold`, if there are at least two.
+    pub fn polarized_factions(&self, threshold: f32) -> &[Faction<'_, K>] {
+        let count = self.factions
+            .iter()
+            .take_while(|faction| faction.share >= threshold)
+            .count();
+        if count >= 2 {
+            &self.factions[..count]
+        } else {
+            &[]
+        }
+    }
+
+    /// Get how polarized the community is about the facet.
+    ///
+    /// If at least two factions have a share of at least `threshold`, this is one minus the
+    /// margin between the two largest, so an even split is `1.0`. Otherwise, the community isn't
+    /// polarized and this is `0.0`. See [`FacetData::contention`] for the individual counterpart.
+    pub fn polarization(&self, threshold: f32) -> f32 {
+        match self.polarized_factions(threshold) {
+            [first, second, ..] => 1.0 - (first.share - second.share),
+            _ => 0.0,
+        }
+    }
+
+    /// Check whether at least two factions have a share of at least `threshold`.
+    pub fn is_polarized(&self, threshold: f32) -> bool {
+        !self.polarized_factions(threshold).is_empty()
+    }
+}
+
+impl<K: KnowledgeTrait> Clone for FacetConsensus<'_, K> {
+    fn clone(&self) -> Self {
+        Self {
+            facet: self.facet,
+            truth: self.truth,
+            factions: self.factions.clone(),
+            distance_from_truth: self.distance_from_truth,
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for FacetConsensus<'_, K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("FacetConsensus")
+            .field("facet", self.facet)
+            .field("truth", self.truth)
+            .field("factions", &self.factions)
+            .field("distance_from_truth", &self.distance_from_truth)
+            .finish()
+    }
+}
+
+/// What a community of holders collectively believes about an entity.
+///
+/// Every holder's [model](EvidenceModel) of the entity counts equally, no matter how much
+/// evidence it has.
+pub struct Consensus<'a, K: KnowledgeTrait> {
+    /// The collective beliefs about each facet that any holder has a belief about.
+    pub facets: KnowledgeMap<&'a K::Facet, FacetConsensus<'a, K>>,
+}
+
+impl<'a, K: KnowledgeTrait + 'a> Consensus<'a, K> {
+    /// Aggregate many holders' models of the same entity.
+    pub fn from_models(models: impl IntoIterator<Item = &'a EvidenceModel<K>>) -> Self {
+        let mut facets: KnowledgeMap<_, (FacetConsensus<K>, f32)> = KnowledgeMap::default();
+
+        for model in models {
+            for (facet, data) in model.facets.iter() {
+                let strongest = if let Some(strongest) = data.strongest() {
+                    strongest
+                } else {
+                    continue;
+                };
+
+                let (consensus, truth_support) = facets.entry(facet).or_insert_with(|| {
+                    let consensus = FacetConsensus {
+                        facet,
+                        truth: &data.truth,
+                        factions: Vec::new(),
+                        distance_from_truth: 0.0,
+                    };
+                    (consensus, 0.0)
+                });
+                *truth_support += data.probability(consensus.truth);
+                let faction = consensus.factions.iter_mut().find(|f| f.value == strongest);
+                if let Some(faction) = faction {
+                    faction.members.push(&model.holder);
+                } else {
+                    consensus.factions.push(Faction {
+                        value: strongest,
+                        members: vec![&model.holder],
+                        share: 0.0,
+                    });
+                }
+            }
+        }
+
+        Consensus {
+            facets: facets
+                .into_iter()
+                .map(|(facet, (mut consensus, truth_support))| {
+                    let holders = consensus.holders() as f32;
+                    for faction in consensus.factions.iter_mut() {
+                        faction.share = faction.members.len() as f32 / holders;
+                    }
+                    consensus.factions.sort_by(|l, r| r.share.total_cmp(&l.share));
+                    consensus.distance_from_truth = 1.0 - truth_support / holders;
+                    (facet, consensus)
+                })
+                .collect(),
+        }
+    }
+
+    /// Aggregate what many minds believe about `regarding`.
+    ///
+    /// Minds without a model of `regarding` (including `regarding`'s own mind) aren't counted.
+    pub fn from_minds(
+        regarding: &K::Entity,
+        minds: impl IntoIterator<Item = &'a Mind<K>>,
+    ) -> Self
+    where
+        K::Entity: Eq + Hash,
+    {
+        Self::from_models(minds.into_iter().filter_map(|mind| mind.get_model(regarding)))
+    }
+
+    /// Get the collective beliefs about a facet, if any holder has a belief about it.
+    pub fn get(&self, facet: &K::Facet) -> Option<&FacetConsensus<'a, K>> {
+        self.facets.get(facet)
+    }
+
+    /// Get every [polarized](FacetConsensus::is_polarized) facet, most polarized first.
+    pub fn polarized(&self, threshold: f32) -> Vec<&FacetConsensus<'a, K>> {
+        let mut polarized: Vec<_> = self.facets
+            .values()
+            .filter(|consensus| consensus.is_polarized(threshold))
+            .collect();
+        polarized.sort_by(|l, r| r.polarization(threshold).total_cmp(&l.polarization(threshold)));
+        polarized
+    }
+}
+
+impl<K: KnowledgeTrait> Clone for Consensus<'_, K> {
+    fn clone(&self) -> Self {
+        Self {
+            facets: self.facets.clone(),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for Consensus<'_, K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("Consensus")
+            .field("facets", &self.facets)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn villages_split_into_factions() {
+        let dave = TestEntity::new("dave", TestValue::Red, TestValue::Smith);
+        let erin = TestEntity::new("erin", TestValue::Green, TestValue::Farmer);
+        let observed = || evidence(EvidenceKind::Observation { location: bob().id() }, 1.0);
+        let mut minds: Vec<_> = [alice(), carol(), dave.clone(), erin.clone(), bob()]
+            .into_iter()
+            .map(Mind::<TestTypes>::new)
+            .collect();
+        for mind in minds[..2].iter_mut() {
+            mind.insert_evidence(&bob(), TestValue::Green, observed());
+        }
+        for mind in minds[2..4].iter_mut() {
+            let dream = evidence(EvidenceKind::Custom(TestKind::Dream), 1.0);
+            mind.insert_evidence(&bob(), TestValue::Red, evidence(dream.kind.clone(), 3.0));
+            mind.insert_evidence(&bob(), TestValue::Green, dream);
+            mind.insert_evidence(&bob(), TestValue::Smith, observed());
+        }
+
+        let consensus = Consensus::from_minds(&bob(), &minds);
+        let hair = consensus.get(&TestFacet::HairColor).unwrap();
+        assert_eq!(hair.holders(), 4);
+        assert_eq!(hair.truth, &TestValue::Green);
+        assert!((hair.distance_from_truth - 0.375).abs() < 1e-6);
+        assert_eq!(hair.agreement(), 0.5);
+        assert_eq!(hair.majority(), None);
+        assert!(!hair.majority_is_true());
+        assert!(hair.is_polarized(0.4));
+        assert_eq!(hair.polarization(0.4), 1.0);
+        assert!(!hair.is_polarized(0.6));
+
+        let job = consensus.get(&TestFacet::Job).unwrap();
+        assert_eq!(job.holders(), 2);
+        assert_eq!(job.majority(), Some(&TestValue::Smith));
+        assert!(job.majority_is_true());
+        assert_eq!(job.agreement(), 1.0);
+        assert_eq!(job.factions[0].members, vec![&dave, &erin]);
+        assert_eq!(consensus.polarized(0.4).len(), 1);
+    }
+}
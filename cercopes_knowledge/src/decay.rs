@@ -0,0 +1,214 @@
+use super::*;
+
+/// A policy describing how evidence weakens over time.
+///
+/// Policies are applied on a tick by [`EvidenceModel::decay`], which only passes the time that
+/// actually elapsed for each piece of evidence: evidence gained partway through a tick (according
+/// to [`KnowledgeTrait::evidence_time`]) only decays for the part of the tick it existed for.
+pub trait DecayPolicy<K: KnowledgeTrait> {
+    /// Get the new strength of a piece of evidence after `elapsed` time has passed.
+    fn decay(&self, evidence: &Evidence<K>, elapsed: f32) -> f32;
+}
+
+/// Get the factor by which something with a particular half-life shrinks after `elapsed` time.
+///
+/// A non-finite half-life (such as [`f32::INFINITY`]) never decays.
+fn half_life_factor(half_life: f32, elapsed: f32) -> f32 {
+    if half_life.is_finite() {
+        0.5_f32.powf(elapsed / half_life)
+    } else {
+        1.0
+    }
+}
+
+/// Halve the strength of all evidence every `half_life` units of time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ExponentialDecay {
+    /// How long it takes for a piece of evidence to lose half of its strength.
+    pub half_life: f32,
+}
+
+impl<K: KnowledgeTrait> DecayPolicy<K> for ExponentialDecay {
+    fn decay(&self, evidence: &Evidence<K>, elapsed: f32) -> f32 {
+        evidence.strength * half_life_factor(self.half_life, elapsed)
+    }
+}
+
+/// Move the strength of all evidence towards zero at a constant rate.
+///
+/// Evidence never crosses zero; it stops decaying once it has no strength left.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LinearDecay {
+    /// How much strength a piece of evidence loses per unit of time.
+    pub rate: f32,
+}
+
+impl<K: KnowledgeTrait> DecayPolicy<K> for LinearDecay {
+    fn decay(&self, evidence: &Evidence<K>, elapsed: f32) -> f32 {
+        let loss = self.rate * elapsed;
+        if evidence.strength > 0.0 {
+            (evidence.strength - loss).max(0.0)
+        } else {
+            (evidence.strength + loss).min(0.0)
+        }
+    }
+}
+
+/// Exponentially decay evidence with a half-life depending on its [principal
+/// kind](Evidence::principal_kind).
+///
+/// For example, an [observation](EvidenceKind::Observation) could be remembered far longer than
+/// an [overheard statement](EvidenceKind::Overheard).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KindHalfLives {
+    /// The half-life of any evidence kind without an entry in `half_lives`.
+    pub default: f32,
+    /// The half-life for each evidence kind.
+    pub half_lives: KnowledgeMap<EvidenceKindTag, f32>,
+}
+
+impl KindHalfLives {
+    /// Create a policy where every evidence kind has the same half-life.
+    pub fn new(default: f32) -> Self {
+        KindHalfLives {
+            default,
+            half_lives: KnowledgeMap::default(),
+        }
+    }
+
+    /// Set the half-life of a particular evidence kind.
+    pub fn with(mut self, kind: EvidenceKindTag, half_life: f32) -> Self {
+        self.half_lives.insert(kind, half_life);
+        self
+    }
+
+    /// Get the half-life of a particular evidence kind.
+    pub fn half_life(&self, kind: EvidenceKindTag) -> f32 {
+        self.half_lives.get(&kind).copied().unwrap_or(self.default)
+    }
+}
+
+impl<K: KnowledgeTrait> DecayPolicy<K> for KindHalfLives {
+    fn decay(&self, evidence: &Evidence<K>, elapsed: f32) -> f32 {
+        let half_life = self.half_life(evidence.principal_kind().tag());
+        evidence.strength * half_life_factor(half_life, elapsed)
+    }
+}
+
+impl<K: KnowledgeTrait> FacetValueData<K> {
+    /// Decay all of the evidence for this value, then recompute the total strength.
+    ///
+    /// See [`EvidenceModel::decay`].
+    pub fn decay<P: DecayPolicy<K>>(&mut self, policy: &P, now: f32, elapsed: f32) {
+        for evidence in self.evidence.iter_mut() {
+            let elapsed = K::evidence_time(&evidence.data)
+                .map_or(elapsed, |time| (now - time).max(0.0).min(elapsed));
+            evidence.strength = policy.decay(evidence, elapsed);
+        }
+
+        self.recompute_total_strength();
+    }
+}
+
+impl<K: KnowledgeTrait> FacetData<K> {
+    /// Decay all of the evidence for this facet, then recompute the total strengths and the
+    /// strongest belief.
+    ///
+    /// See [`EvidenceModel::decay`].
+    pub fn decay<P: DecayPolicy<K>>(&mut self, policy: &P, now: f32, elapsed: f32) {
+        for value in self.values.values_mut() {
+            value.decay(policy, now, elapsed);
+        }
+
+        self.recompute_strongest();
+    }
+}
+
+impl<K: KnowledgeTrait> EvidenceModel<K> {
+    /// Decay all of the evidence in this model according to a [decay policy](DecayPolicy).
+    ///
+    /// `elapsed` is how much time has passed since the last tick and `now` is the current time.
+    /// Evidence with a [timestamp](KnowledgeTrait::evidence_time) after the start of the tick only
+    /// decays for the time since it was gained. The total strengths and the strongest beliefs are
+    /// recomputed afterwards.
+    pub fn decay<P: DecayPolicy<K>>(&mut self, policy: &P, now: f32, elapsed: f32) {
+        self.update_facets(|data| data.decay(policy, now, elapsed));
+    }
+}
+
+impl<K: KnowledgeTrait> MentalModel<K> {
+    /// Decay all of the evidence in this model according to a [decay policy](DecayPolicy).
+    ///
+    /// Does nothing for a [reflexive model](ReflexiveModel). See [`EvidenceModel::decay`].
+    pub fn decay<P: DecayPolicy<K>>(&mut self, policy: &P, now: f32, elapsed: f32) {
+        if let MentalModel::Evidence(evidence) = self {
+            evidence.decay(policy, now, elapsed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn exponential_halves_per_half_life() {
+        let mut model = EvidenceModel::new(alice(), bob());
+        model.insert_evidence(TestValue::Green, evidence(EvidenceKind::Implantation, 4.0));
+        model.insert_evidence(TestValue::Red, timed(evidence(EvidenceKind::Implantation, 4.0), 5.0));
+        model.decay(&ExponentialDecay { half_life: 10.0 }, 10.0, 10.0);
+        let hair = &model.facets[&TestFacet::HairColor];
+        assert!((hair.values[&TestValue::Green].total_strength - 2.0).abs() < 1e-5);
+        // only existed for half of the tick
+        assert!((hair.values[&TestValue::Red].total_strength - 4.0 / 2_f32.sqrt()).abs() < 1e-5);
+        assert_eq!(hair.strongest, Some(TestValue::Red));
+    }
+
+    #[test]
+    fn negative_elapsed_applies_to_all_evidence() {
+        let mut model = EvidenceModel::new(alice(), bob());
+        model.insert_evidence(TestValue::Green, evidence(EvidenceKind::Implantation, 1.0));
+        model.insert_evidence(TestValue::Red, timed(evidence(EvidenceKind::Implantation, 1.0), 5.0));
+        model.decay(&ExponentialDecay { half_life: 10.0 }, 10.0, -10.0);
+        let hair = &model.facets[&TestFacet::HairColor];
+        assert!((hair.values[&TestValue::Green].total_strength - 2.0).abs() < 1e-5);
+        assert!((hair.values[&TestValue::Red].total_strength - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn linear_stops_at_zero() {
+        let mut model = EvidenceModel::new(alice(), bob());
+        model.insert_evidence(TestValue::Green, evidence(EvidenceKind::Implantation, 1.0));
+        model.insert_evidence(TestValue::Green, evidence(EvidenceKind::Implantation, -1.0));
+        model.insert_evidence(TestValue::Red, evidence(EvidenceKind::Implantation, 3.0));
+        model.decay(&LinearDecay { rate: 0.5 }, 4.0, 4.0);
+        let hair = &model.facets[&TestFacet::HairColor];
+        let strengths: Vec<_> = hair.values[&TestValue::Green].evidence
+            .iter()
+            .map(|evidence| evidence.strength)
+            .collect();
+        assert_eq!(strengths, vec![0.0, 0.0]);
+        assert_eq!(hair.values[&TestValue::Red].total_strength, 1.0);
+        assert_eq!(hair.strongest, Some(TestValue::Red));
+    }
+
+    #[test]
+    fn kind_half_lives_flip_strongest() {
+        let mut model = EvidenceModel::new(alice(), bob());
+        model.insert_evidence(TestValue::Green, evidence(EvidenceKind::Observation {
+            location: carol().id(),
+        }, 1.0));
+        model.insert_evidence(TestValue::Red, evidence(EvidenceKind::Statement {
+            source: carol().id(),
+            location: carol().id(),
+        }, 2.0).mutated());
+        model.recompute_total_strengths();
+        model.recompute_strongest();
+        assert_eq!(model.get_strongest_belief(&TestFacet::HairColor), Some(&TestValue::Red));
+        let policy = KindHalfLives::new(1.0)
+            .with(EvidenceKindTag::Observation, f32::INFINITY);
+        model.decay(&policy, 2.0, 2.0);
+        assert_eq!(model.get_strongest_belief(&TestFacet::HairColor), Some(&TestValue::Green));
+    }
+}
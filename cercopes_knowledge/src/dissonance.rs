@@ -0,0 +1,177 @@
+use super::*;
+
+/// A value of a [contested facet](Contention), with its support and who stated it.
+pub struct ContestedValue<'a, K: KnowledgeTrait> {
+    /// The contested value.
+    pub value: &'a K::FacetValue,
+    /// The probability the holder assigns to this value.
+    pub probability: f32,
+    /// Every entity that (originally) stated this value to the holder.
+    pub sources: Vec<&'a K::EntityId>,
+}
+
+impl<K: KnowledgeTrait> Clone for ContestedValue<'_, K> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value,
+            probability: self.probability,
+            sources: self.sources.clone(),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for ContestedValue<'_, K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("ContestedValue")
+            .field("value", self.value)
+            .field("probability", &self.probability)
+            .field("sources", &self.sources)
+            .finish()
+    }
+}
+
+/// A facet where multiple values have significant support.
+pub struct Contention<'a, K: KnowledgeTrait> {
+    /// The contested facet.
+    pub facet: &'a K::Facet,
+    /// How contested the facet is, from `0.0` (uncontested) to `1.0` (an even split).
+    ///
+    /// See [`FacetData::contention`].
+    pub contention: f32,
+    /// The values with significant support, most probable first.
+    pub values: Vec<ContestedValue<'a, K>>,
+}
+
+impl<K: KnowledgeTrait> Clone for Contention<'_, K> {
+    fn clone(&self) -> Self {
+        Self {
+            facet: self.facet,
+            contention: self.contention,
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for Contention<'_, K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("Contention")
+            .field("facet", self.facet)
+            .field("contention", &self.contention)
+            .field("values", &self.values)
+            .finish()
+    }
+}
+
+impl<K: KnowledgeTrait> FacetData<K> {
+    /// Get every entity that (originally) stated a particular value to the holder.
+    ///
+    /// See [`Evidence::source`]. Each source is only listed once.
+    pub fn sources(&self, value: &K::FacetValue) -> Vec<&K::EntityId> {
+        let mut sources = Vec::new();
+
+        if let Some(data) = self.values.get(value) {
+            for source in data.evidence.iter().filter_map(Evidence::source) {
+                if !sources.contains(&source) {
+                    sources.push(source);
+                }
+            }
+        }
+
+        sources
+    }
+
+    /// Get the values with significant support, most probable first.
+    ///
+    /// A value has significant support if its [probability](FacetData::probability) is at least
+    /// `threshold`.
+    pub fn contested_values(&self, threshold: f32) -> Vec<ContestedValue<'_, K>> {
+        self.distribution()
+            .into_iter()
+            .filter(|(_, probability)| *probability >= threshold)
+            .map(|(value, probability)| ContestedValue {
+                value,
+                probability,
+                sources: self.sources(value),
+            })
+            .collect()
+    }
+
+    /// Get how contested this facet is.
+    ///
+    /// If at least two values have significant support (see [`FacetData::contested_values`]),
+    /// this is one minus the margin between the two most probable values, so an even split is
+    /// `1.0`. Otherwise, the facet is uncontested and this is `0.0`.
+    pub fn contention(&self, threshold: f32) -> f32 {
+        match self.contested_values(threshold).as_slice() {
+            [first, second, ..] => 1.0 - (first.probability - second.probability),
+            _ => 0.0,
+        }
+    }
+
+    /// Check whether at least two values have significant support.
+    pub fn is_contested(&self, threshold: f32) -> bool {
+        self.contested_values(threshold).len() >= 2
+    }
+}
+
+impl<K: KnowledgeTrait> EvidenceModel<K> {
+    /// Get every contested facet in this model, most contested first.
+    ///
+    /// A facet is contested if at least two values have a [probability](FacetData::probability)
+    /// of at least `threshold`.
+    pub fn contested_facets(&self, threshold: f32) -> Vec<Contention<'_, K>> {
+        let mut contested: Vec<_> = self.facets
+            .iter()
+            .filter_map(|(facet, data)| {
+                let values = data.contested_values(threshold);
+                if let [first, second, ..] = values.as_slice() {
+                    Some(Contention {
+                        facet,
+                        contention: 1.0 - (first.probability - second.probability),
+                        values,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect();
+        contested.sort_by(|l, r| r.contention.total_cmp(&l.contention));
+        contested
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn contested_facets_are_ranked() {
+        let dave = TestEntity::new("dave", TestValue::Red, TestValue::Smith);
+        let mut model = EvidenceModel::new(alice(), bob());
+        model.insert_evidence(TestValue::Red, statement(carol(), 1.0));
+        model.insert_evidence(TestValue::Red, statement(carol(), 1.5).mutated());
+        model.insert_evidence(TestValue::Green, statement(dave.clone(), 2.0));
+        model.insert_evidence(TestValue::Blue, evidence(EvidenceKind::Implantation, 0.1));
+        model.insert_evidence(TestValue::Farmer, statement(carol(), 3.0));
+        model.insert_evidence(TestValue::Smith, statement(dave.clone(), 1.0));
+        model.recompute_total_strengths();
+        model.recompute_strongest();
+
+        let contested = model.contested_facets(0.2);
+        assert_eq!(contested.len(), 2);
+        assert_eq!(*contested[0].facet, TestFacet::HairColor);
+        assert_eq!(contested[0].values.len(), 2);
+        assert_eq!(contested[0].values[0].sources, vec!["carol"]);
+        assert_eq!(contested[0].values[1].sources, vec!["dave"]);
+        assert_eq!(*contested[1].facet, TestFacet::Job);
+        assert!((contested[1].contention - 0.5).abs() < 1e-6);
+
+        assert!(model.contested_facets(0.3).iter().all(|c| *c.facet == TestFacet::HairColor));
+        let hair = &model.facets[&TestFacet::HairColor];
+        assert!(!hair.is_contested(0.5));
+        assert_eq!(hair.contention(0.5), 0.0);
+    }
+}
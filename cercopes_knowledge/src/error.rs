@@ -0,0 +1,79 @@
+use super::*;
+
+/// An error from an implementation of one of the knowledge traits.
+///
+/// Every fallible method has an infallible counterpart (such as [`EvidenceModel::new`] for
+/// [`EvidenceModel::try_new`]) that panics on these errors instead.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KnowledgeError {
+    /// An entity didn't have a true value for a facet that is relevant to it.
+    MissingTruth {
+        /// The entity, as formatted by [`Debug`].
+        entity: String,
+        /// The facet, as formatted by [`Debug`].
+        facet: String,
+    },
+    /// An implementation of a knowledge trait failed, such as a scripted callback.
+    Callback(String),
+}
+
+impl KnowledgeError {
+    /// Create an error for an entity missing the true value of a relevant facet.
+    pub fn missing_truth(entity: &impl Debug, facet: &impl Debug) -> Self {
+        KnowledgeError::MissingTruth {
+            entity: format!("{entity:?}"),
+            facet: format!("{facet:?}"),
+        }
+    }
+
+    /// Create an error for a failed callback.
+    pub fn callback(err: impl std::fmt::Display) -> Self {
+        KnowledgeError::Callback(err.to_string())
+    }
+}
+
+impl std::fmt::Display for KnowledgeError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            KnowledgeError::MissingTruth { entity, facet } => {
+                write!(f, "{entity} did not have a true value for the relevant facet {facet}")
+            },
+            KnowledgeError::Callback(err) => write!(f, "callback failed: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for KnowledgeError {}
+
+/// Unwrap the result of a fallible method for its infallible counterpart.
+pub(crate) fn or_panic<T>(result: Result<T, KnowledgeError>) -> T {
+    result.unwrap_or_else(|err| panic!("{err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn missing_truths_are_errors() {
+        let err = FacetData::<TestTypes>::try_new(&alice(), &TestFacet::Feeling).unwrap_err();
+        assert_eq!(err, KnowledgeError::missing_truth(&alice(), &TestFacet::Feeling));
+        assert!(err.to_string().ends_with("for the relevant facet Feeling"));
+        assert_eq!(alice().try_facet_truth(&TestFacet::Job), Ok(TestValue::Farmer));
+        assert!(EvidenceModel::<TestTypes>::try_new(alice(), bob()).is_ok());
+
+        let mut mind = Mind::<TestTypes>::new(alice());
+        mind.get_model_mut(&bob()).facets.insert(
+            TestFacet::Feeling,
+            FacetData::from_truth(TestValue::Friend),
+        );
+        assert!(matches!(mind.try_update_truths(), Err(KnowledgeError::MissingTruth { .. })));
+        let model = mind.get_relation_mut(&bob(), &carol());
+        model.from = carol();
+        assert!(matches!(model.try_update_truths(), Err(KnowledgeError::MissingTruth { .. })));
+        model.facets.clear();
+        assert!(model.try_get_facet_data(TestFacet::Feeling).is_err());
+        assert!(RelationModel::<TestTypes>::try_new(alice(), bob(), bob()).is_err());
+    }
+}
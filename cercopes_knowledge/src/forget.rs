@@ -0,0 +1,407 @@
+use super::*;
+
+/// How a holder forgets evidence, so that their memory doesn't grow without bound.
+///
+/// Forgetting happens in order: redundant evidence is [merged](ForgetPolicy::merge), then weak
+/// and old evidence is pruned, then the weakest evidence is pruned until the models are within
+/// their budgets.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForgetPolicy {
+    /// Whether to merge evidence of the same kind (including its source, recipient and location)
+    /// and origin into a single record.
+    ///
+    /// The merged record keeps the data of the newest piece of evidence, by its
+    /// [timestamp](KnowledgeTrait::evidence_time), and the sum of the strengths. Mutated evidence
+    /// is never merged. Since merging happens before pruning, weak pieces of evidence can be
+    /// merged into a record that is strong enough to be kept.
+    pub merge: bool,
+    /// Evidence with a strength (in magnitude) below this is forgotten.
+    pub min_strength: f32,
+    /// The current time, used for `max_age`.
+    pub now: f32,
+    /// Evidence with a [timestamp](KnowledgeTrait::evidence_time) older than this is forgotten.
+    pub max_age: Option<f32>,
+    /// The most pieces of evidence that a single model can hold.
+    pub model_budget: Option<usize>,
+    /// The most pieces of evidence that all of a [mind's](Mind) models can hold together.
+    pub holder_budget: Option<usize>,
+}
+
+impl ForgetPolicy {
+    /// Create a policy that forgets nothing.
+    pub fn new() -> Self {
+        ForgetPolicy {
+            merge: false,
+            min_strength: 0.0,
+            now: 0.0,
+            max_age: None,
+            model_budget: None,
+            holder_budget: None,
+        }
+    }
+
+    /// Merge redundant evidence.
+    pub fn with_merging(mut self) -> Self {
+        self.merge = true;
+        self
+    }
+
+    /// Forget evidence weaker than `min_strength`.
+    pub fn with_min_strength(mut self, min_strength: f32) -> Self {
+        self.min_strength = min_strength;
+        self
+    }
+
+    /// Forget evidence older than `max_age` at the time `now`.
+    pub fn with_max_age(mut self, now: f32, max_age: f32) -> Self {
+        self.now = now;
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Limit how much evidence a single model can hold.
+    pub fn with_model_budget(mut self, budget: usize) -> Self {
+        self.model_budget = Some(budget);
+        self
+    }
+
+    /// Limit how much evidence all of a mind's models can hold together.
+    pub fn with_holder_budget(mut self, budget: usize) -> Self {
+        self.holder_budget = Some(budget);
+        self
+    }
+
+    /// Check whether a piece of evidence is too weak or too old to remember.
+    fn is_forgettable<K: KnowledgeTrait>(&self, evidence: &Evidence<K>) -> bool {
+        evidence.strength.abs() < self.min_strength
+            || self.max_age.zip(K::evidence_time(&evidence.data)).is_some_and(|(max_age, time)| {
+                self.now - time > max_age
+            })
+    }
+}
+
+impl Default for ForgetPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How much was forgotten by [forgetting](EvidenceModel::forget).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ForgetStats {
+    /// The number of pieces of evidence that were merged into another.
+    pub merged: usize,
+    /// The number of pieces of evidence that were forgotten outright.
+    pub pruned: usize,
+    /// The total strength (in magnitude) of the forgotten evidence.
+    pub pruned_strength: f32,
+}
+
+impl ForgetStats {
+    fn prune<K: KnowledgeTrait>(&mut self, evidence: &Evidence<K>) {
+        self.pruned += 1;
+        self.pruned_strength += evidence.strength.abs();
+    }
+}
+
+impl std::ops::AddAssign for ForgetStats {
+    fn add_assign(&mut self, other: Self) {
+        self.merged += other.merged;
+        self.pruned += other.pruned;
+        self.pruned_strength += other.pruned_strength;
+    }
+}
+
+/// Get how weak a piece of evidence is, for ordering: the weakest and then oldest comes first.
+fn weakness<K: KnowledgeTrait>(evidence: &Evidence<K>) -> (f32, f32) {
+    let time = K::evidence_time(&evidence.data).unwrap_or(f32::NEG_INFINITY);
+    (evidence.strength.abs(), time)
+}
+
+fn compare_weakness(l: (f32, f32), r: (f32, f32)) -> std::cmp::Ordering {
+    l.0.total_cmp(&r.0).then(l.1.total_cmp(&r.1))
+}
+
+impl<K: KnowledgeTrait> FacetValueData<K>
+where
+    K::Data: PartialEq,
+    K::CustomKind: PartialEq,
+{
+    /// Merge redundant evidence and forget weak and old evidence for this value.
+    ///
+    /// Budgets are ignored. See [`EvidenceModel::forget`].
+    pub fn forget(&mut self, policy: &ForgetPolicy) -> ForgetStats {
+        let mut stats = ForgetStats::default();
+
+        self.update_evidence(|evidence| {
+            if policy.merge {
+                let mut merged: Vec<Evidence<K>> = Vec::with_capacity(evidence.len());
+                for new in evidence.drain(..) {
+                    let redundant = merged.iter_mut().find(|old| {
+                        !matches!(new.kind, EvidenceKind::Mutation { .. })
+                            && old.kind == new.kind
+                            && old.origin == new.origin
+                    });
+                    if let Some(old) = redundant {
+                        old.strength += new.strength;
+                        if K::evidence_time(&new.data) >= K::evidence_time(&old.data) {
+                            old.data = new.data;
+                        }
+                        stats.merged += 1;
+                    } else {
+                        merged.push(new);
+                    }
+                }
+                *evidence = merged;
+            }
+
+            evidence.retain(|evidence| {
+                let forget = policy.is_forgettable(evidence);
+                if forget {
+                    stats.prune(evidence);
+                }
+                !forget
+            });
+        });
+
+        stats
+    }
+}
+
+impl<K: KnowledgeTrait> EvidenceModel<K>
+where
+    K::Data: PartialEq,
+    K::CustomKind: PartialEq,
+{
+    /// Forget evidence in this model according to a [forgetting policy](ForgetPolicy).
+    ///
+    /// The holder budget is ignored; see [`Mind::forget`]. Updates the total strengths and the
+    /// strongest beliefs.
+    pub fn forget(&mut self, policy: &ForgetPolicy) -> ForgetStats {
+        let mut stats = ForgetStats::default();
+        let facets: Vec<_> = self.facets.keys().cloned().collect();
+
+        for facet in facets {
+            self.update_facet(facet, |data| {
+                let values: Vec<_> = data.values().keys().cloned().collect();
+                for value in values {
+                    stats += data.update_value(value, |data| data.forget(policy));
+                }
+            });
+        }
+
+        if let Some(budget) = policy.model_budget {
+            stats += self.forget_weakest(self.evidence_count().saturating_sub(budget));
+        }
+
+        stats
+    }
+}
+
+impl<K: KnowledgeTrait> EvidenceModel<K> {
+    /// Get how many pieces of evidence this model holds.
+    pub fn evidence_count(&self) -> usize {
+        self.facets
+            .values()
+            .flat_map(|data| data.values().values())
+            .map(|data| data.evidence().len())
+            .sum()
+    }
+
+    /// Get where each piece of evidence is and how weak it is, weakest first.
+    fn weakest(&self) -> Vec<((f32, f32), &K::FacetValue, usize)> {
+        let mut weakest: Vec<_> = self.facets
+            .values()
+            .flat_map(|data| data.values().iter())
+            .flat_map(|(value, data)| {
+                data.evidence()
+                    .iter()
+                    .enumerate()
+                    .map(move |(i, evidence)| (weakness(evidence), value, i))
+            })
+            .collect();
+        weakest.sort_by(|(l, ..), (r, ..)| compare_weakness(*l, *r));
+        weakest
+    }
+
+    /// Forget the `count` weakest pieces of evidence, oldest first among equals.
+    pub fn forget_weakest(&mut self, count: usize) -> ForgetStats {
+        if count == 0 {
+            return ForgetStats::default();
+        }
+
+        let mut forgotten: KnowledgeMap<K::FacetValue, Vec<usize>> = KnowledgeMap::default();
+        for (_, value, i) in self.weakest().into_iter().take(count) {
+            forgotten.entry(value.clone()).or_default().push(i);
+        }
+        self.forget_marked(forgotten)
+    }
+
+    /// Forget the evidence at particular indices of particular values.
+    fn forget_marked(&mut self, forgotten: KnowledgeMap<K::FacetValue, Vec<usize>>) -> ForgetStats {
+        let mut stats = ForgetStats::default();
+
+        for (value, mut indices) in forgotten {
+            indices.sort_unstable();
+            self.update_facet(value.facet(), |data| {
+                data.update_value(value, |data| {
+                    data.update_evidence(|evidence| {
+                        let mut i = 0;
+                        evidence.retain(|evidence| {
+                            let forget = indices.binary_search(&i).is_ok();
+                            if forget {
+                                stats.prune(evidence);
+                            }
+                            i += 1;
+                            !forget
+                        });
+                    });
+                });
+            });
+        }
+
+        stats
+    }
+}
+
+impl<K: KnowledgeTrait> Mind<K>
+where
+    K::Entity: Eq + Hash,
+    K::Data: PartialEq,
+    K::CustomKind: PartialEq,
+{
+    /// Forget evidence in every model according to a [forgetting policy](ForgetPolicy).
+    ///
+    /// Each model is forgotten as by [`EvidenceModel::forget`], and then the weakest evidence
+    /// across all models is forgotten until they are within the holder budget. Relation and nested
+    /// models are forgotten too, but don't count towards the holder budget.
+    pub fn forget(&mut self, policy: &ForgetPolicy) -> ForgetStats {
+        let mut stats = ForgetStats::default();
+        for model in self.models.values_mut() {
+            stats += model.forget(policy);
+        }
+        for model in self.relation_models_mut() {
+            stats += model.forget(policy);
+        }
+        for model in self.nested_models_mut() {
+            stats += model.forget(policy);
+        }
+
+        let budget = if let Some(budget) = policy.holder_budget {
+            budget
+        } else {
+            return stats;
+        };
+        let count: usize = self.models.values().map(EvidenceModel::evidence_count).sum();
+        if count <= budget {
+            return stats;
+        }
+
+        let mut weakest: Vec<_> = self.models
+            .iter()
+            .flat_map(|(entity, model)| {
+                model
+                    .weakest()
+                    .into_iter()
+                    .map(move |(weakness, value, i)| (weakness, entity, value, i))
+            })
+            .collect();
+        weakest.sort_by(|(l, ..), (r, ..)| compare_weakness(*l, *r));
+        let mut forgotten: KnowledgeMap<K::Entity, KnowledgeMap<K::FacetValue, Vec<usize>>> =
+            KnowledgeMap::default();
+        for (_, entity, value, i) in weakest.into_iter().take(count - budget) {
+            forgotten
+                .entry(entity.clone())
+                .or_default()
+                .entry(value.clone())
+                .or_default()
+                .push(i);
+        }
+
+        for (entity, forgotten) in forgotten {
+            if let Some(model) = self.models.get_mut(&entity) {
+                stats += model.forget_marked(forgotten);
+            }
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn models_merge_prune_and_keep_to_budget() {
+        let mut model = EvidenceModel::new(alice(), bob());
+        model.insert_evidence(TestValue::Red, timed(statement(carol(), 1.0), 1.0));
+        model.insert_evidence(TestValue::Red, timed(statement(carol(), 2.0), 2.0));
+        model.insert_evidence(TestValue::Red, timed(statement(bob(), 0.5), 3.0));
+        model.insert_evidence(TestValue::Red, timed(statement(bob(), 0.5), 4.0).mutated());
+        model.insert_evidence(TestValue::Green, timed(statement(bob(), 0.1), 5.0));
+        model.insert_evidence(TestValue::Green, timed(statement(carol(), 4.0), -20.0));
+        model.insert_evidence(TestValue::Smith, timed(statement(carol(), 0.5), 6.0));
+        model.insert_evidence(TestValue::Farmer, timed(statement(carol(), 0.6), 7.0));
+        assert_eq!(model.evidence_count(), 8);
+
+        let policy = ForgetPolicy::new()
+            .with_merging()
+            .with_min_strength(0.2)
+            .with_max_age(10.0, 20.0)
+            .with_model_budget(4);
+        let stats = model.forget(&policy);
+        assert_eq!(stats.merged, 1);
+        assert_eq!(stats.pruned, 3);
+        assert!((stats.pruned_strength - 4.6).abs() < 1e-6);
+        assert_eq!(model.evidence_count(), 4);
+        let red = model.get_value(&TestValue::Red).unwrap();
+        assert_eq!(red.evidence().len(), 2);
+        assert_eq!(red.evidence()[0].data, 2.0);
+        assert_eq!(red.total_strength(), 3.5);
+        assert!(model.get_value(&TestValue::Green).unwrap().evidence().is_empty());
+        assert_eq!(model.get_strongest_belief(&TestFacet::HairColor), Some(&TestValue::Red));
+        assert_eq!(model.get_strongest_belief(&TestFacet::Job), Some(&TestValue::Farmer));
+    }
+
+    #[test]
+    fn minds_keep_to_the_holder_budget() {
+        let mut mind = Mind::<TestTypes>::new(alice());
+        mind.insert_evidence(&bob(), TestValue::Red, statement(carol(), 1.0));
+        mind.insert_evidence(&bob(), TestValue::Smith, statement(carol(), 3.0));
+        mind.insert_evidence(&carol(), TestValue::Red, statement(bob(), 2.0));
+        mind.insert_evidence(&carol(), TestValue::Farmer, timed(statement(bob(), 2.0), 1.0));
+
+        let stats = mind.forget(&ForgetPolicy::new().with_holder_budget(2));
+        assert_eq!(stats.pruned, 2);
+        assert_eq!(mind.models[&bob()].get_strongest_belief(&TestFacet::HairColor), None);
+        assert_eq!(mind.models[&bob()].evidence_count(), 1);
+        let carol_model = &mind.models[&carol()];
+        assert_eq!(carol_model.get_strongest_belief(&TestFacet::Job), Some(&TestValue::Farmer));
+        assert_eq!(carol_model.evidence_count(), 1);
+    }
+
+    #[test]
+    fn only_identical_kinds_and_origins_merge() {
+        let mut data = FacetValueData::default();
+        let elsewhere = Evidence {
+            kind: EvidenceKind::Statement { source: carol().id(), location: bob().id() },
+            ..timed(statement(carol(), 1.0), 1.0)
+        };
+        let passed_on = Evidence {
+            origin: Some(Box::new(statement(bob(), 1.0))),
+            ..timed(statement(carol(), 1.0), 2.0)
+        };
+        data.push_evidence(timed(statement(carol(), 1.0), 3.0));
+        data.push_evidence(elsewhere);
+        data.push_evidence(passed_on);
+        data.push_evidence(statement(carol(), 1.0));
+
+        let stats = data.forget(&ForgetPolicy::new().with_merging());
+        assert_eq!(stats.merged, 1);
+        assert_eq!(data.evidence().len(), 3);
+        assert_eq!(data.evidence()[0].strength, 2.0);
+        assert_eq!(data.evidence()[0].data, 3.0);
+        assert!(data.evidence()[2].origin.is_some());
+    }
+}
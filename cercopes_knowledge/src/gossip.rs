@@ -0,0 +1,346 @@
+use super::*;
+
+/// What a speaker claims about a facet of the regarded entity when telling a listener about it.
+pub enum Claim<K: KnowledgeTrait> {
+    /// The speaker states their strongest belief of the facet.
+    Strongest(K::Facet),
+    /// The speaker states a particular value that they think is true.
+    Value(K::FacetValue),
+    /// The speaker states a particular value that they know isn't (necessarily) true.
+    Lie(K::FacetValue),
+}
+
+impl<K: KnowledgeTrait> Claim<K> {
+    /// Get the stated value and whether the speaker is lying, given the speaker's strongest
+    /// belief of a facet.
+    ///
+    /// Returns `None` if the speaker was meant to state a belief they don't have.
+    fn resolve<'a, F>(self, strongest: F) -> Option<(K::FacetValue, bool)>
+    where
+        F: FnOnce(&K::Facet) -> Option<&'a K::FacetValue>,
+        K: 'a,
+    {
+        match self {
+            Claim::Strongest(facet) => strongest(&facet).map(|value| (value.clone(), false)),
+            Claim::Value(value) => Some((value, false)),
+            Claim::Lie(value) => Some((value, true)),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Clone for Claim<K> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Strongest(facet) => Self::Strongest(facet.clone()),
+            Self::Value(value) => Self::Value(value.clone()),
+            Self::Lie(value) => Self::Lie(value.clone()),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for Claim<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::Strongest(facet) => f.debug_tuple("Strongest").field(facet).finish(),
+            Self::Value(value) => f.debug_tuple("Value").field(value).finish(),
+            Self::Lie(value) => f.debug_tuple("Lie").field(value).finish(),
+        }
+    }
+}
+
+/// The details of a single act of telling, shared by the speaker's and listener's evidence.
+struct Telling<K: KnowledgeTrait> {
+    speaker: K::EntityId,
+    listener: K::EntityId,
+    location: K::EntityId,
+    data: K::Data,
+    strength: f32,
+    /// The evidence the claim was passed on from, if provenance is tracked.
+    origin: Option<Evidence<K>>,
+}
+
+impl<K: KnowledgeTrait> Telling<K> {
+    /// The evidence the speaker gains by saying something.
+    fn said(&self, lie: bool) -> Evidence<K> {
+        let recipient = self.listener.clone();
+        let location = self.location.clone();
+        Evidence {
+            data: self.data.clone(),
+            kind: if lie {
+                EvidenceKind::Lie { recipient, location }
+            } else {
+                EvidenceKind::Declaration { recipient, location }
+            },
+            strength: self.strength,
+            weight: 1.0,
+            origin: None,
+        }
+    }
+
+    /// Record where the claim came from, if [provenance is
+    /// tracked](KnowledgeTrait::TRACK_PROVENANCE).
+    ///
+    /// A lie originates from the lie itself, and anything else from the speaker's `supporting`
+    /// evidence for the claimed value.
+    fn trace(&mut self, lie: bool, supporting: Option<&FacetValueData<K>>) {
+        if K::TRACK_PROVENANCE {
+            self.origin = if lie {
+                Some(self.said(true))
+            } else {
+                supporting.and_then(FacetValueData::original_evidence).cloned()
+            };
+        }
+    }
+
+    /// The evidence the listener gains by hearing something.
+    fn heard(&self) -> Evidence<K> {
+        Evidence {
+            data: self.data.clone(),
+            kind: EvidenceKind::Statement {
+                source: self.speaker.clone(),
+                location: self.location.clone(),
+            },
+            strength: self.strength,
+            weight: 1.0,
+            origin: self.origin.clone().map(Box::new),
+        }
+    }
+
+    /// The evidence a bystander gains by overhearing something.
+    fn overheard(&self) -> Evidence<K> {
+        Evidence {
+            data: self.data.clone(),
+            kind: EvidenceKind::Overheard {
+                source: self.speaker.clone(),
+                recipient: self.listener.clone(),
+                location: self.location.clone(),
+            },
+            strength: self.strength,
+            weight: 1.0,
+            origin: self.origin.clone().map(Box::new),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> EvidenceModel<K>
+where
+    K::Entity: Eq,
+{
+    /// Tell the holder of `listener` something about the entity this model regards.
+    ///
+    /// `listener` must be the listener's model of the same entity, which is checked in debug
+    /// builds. The listener gains [statement](EvidenceKind::Statement) evidence for the claimed
+    /// value, and the speaker gains [declaration](EvidenceKind::Declaration) evidence for it (or
+    /// [lie](EvidenceKind::Lie) evidence, if it's a [lie](Claim::Lie)). If [provenance is
+    /// tracked](KnowledgeTrait::TRACK_PROVENANCE), the listener's evidence records its
+    /// [origin](Evidence::origin).
+    ///
+    /// Returns the claimed value, or `None` if the speaker was meant to state their strongest
+    /// belief but doesn't have one.
+    pub fn tell(
+        &mut self,
+        listener: &mut EvidenceModel<K>,
+        claim: Claim<K>,
+        location: K::EntityId,
+        data: K::Data,
+        strength: f32,
+    ) -> Option<K::FacetValue> {
+        debug_assert!(listener.regarding == self.regarding);
+        let (value, lie) = claim.resolve(|facet| self.get_strongest_belief(facet))?;
+        let mut telling = Telling {
+            speaker: self.holder.id(),
+            listener: listener.holder.id(),
+            location,
+            data,
+            strength,
+            origin: None,
+        };
+        telling.trace(lie, self.get_value(&value));
+        self.insert_evidence(value.clone(), telling.said(lie));
+        listener.insert_evidence(value.clone(), telling.heard());
+        Some(value)
+    }
+}
+
+impl<K: KnowledgeTrait> Mind<K>
+where
+    K::Entity: Eq + Hash,
+{
+    /// Tell the holder of `listener` something about an entity.
+    ///
+    /// This is like [`EvidenceModel::tell`], except `regarding` may also be the speaker or the
+    /// listener. Nobody gains evidence about themselves, since they always know their own facets.
+    /// If [nested beliefs are tracked](KnowledgeTrait::TRACK_NESTED_BELIEFS), both also [infer
+    /// what the other now believes](Mind::infer_nested).
+    pub fn tell(
+        &mut self,
+        listener: &mut Mind<K>,
+        regarding: &K::Entity,
+        claim: Claim<K>,
+        location: K::EntityId,
+        data: K::Data,
+        strength: f32,
+    ) -> Option<K::FacetValue> {
+        self.tell_within_earshot(listener, [], regarding, claim, location, data, strength)
+    }
+
+    /// Tell the holder of `listener` something about an entity, while the holders of
+    /// `eavesdroppers` overhear it.
+    ///
+    /// This is like [`Mind::tell`], and each eavesdropper also gains
+    /// [overheard](EvidenceKind::Overheard) evidence for the claimed value, unless it's about
+    /// themselves. Overheard evidence records its [origin](Evidence::origin) like the listener's.
+    #[allow(clippy::too_many_arguments)]
+    pub fn tell_within_earshot<'a>(
+        &mut self,
+        listener: &mut Mind<K>,
+        eavesdroppers: impl IntoIterator<Item = &'a mut Mind<K>>,
+        regarding: &K::Entity,
+        claim: Claim<K>,
+        location: K::EntityId,
+        data: K::Data,
+        strength: f32,
+    ) -> Option<K::FacetValue>
+    where
+        K: 'a,
+    {
+        let about_speaker = regarding == self.holder();
+        let about_listener = regarding == listener.holder();
+        let (value, lie) = claim.resolve(|facet| self.get_strongest_belief(regarding, facet))?;
+        let mut telling = Telling {
+            speaker: self.holder().id(),
+            listener: listener.holder().id(),
+            location,
+            data,
+            strength,
+            origin: None,
+        };
+        let supporting = self.get_model(regarding).and_then(|model| model.get_value(&value));
+        telling.trace(lie, supporting);
+        if !about_speaker {
+            self.get_model_mut(regarding).insert_evidence(value.clone(), telling.said(lie));
+        }
+        if !about_listener {
+            listener.get_model_mut(regarding).insert_evidence(value.clone(), telling.heard());
+        }
+        // the speaker and the listener are the only believers that telling implies
+        let parties = [self.holder().clone(), listener.holder().clone()];
+        if K::TRACK_NESTED_BELIEFS {
+            self.infer_nested(regarding, &value, &telling.said(lie), &parties[..]);
+            listener.infer_nested(regarding, &value, &telling.heard(), &parties[..]);
+        }
+        for eavesdropper in eavesdroppers {
+            if regarding != eavesdropper.holder() {
+                let model = eavesdropper.get_model_mut(regarding);
+                model.insert_evidence(value.clone(), telling.overheard());
+            }
+            if K::TRACK_NESTED_BELIEFS {
+                eavesdropper.infer_nested(regarding, &value, &telling.overheard(), &parties[..]);
+            }
+        }
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn strongest_belief_is_declared_and_heard() {
+        let mut speaker = EvidenceModel::new(alice(), carol());
+        let mut listener = EvidenceModel::new(bob(), carol());
+        speaker.insert_evidence(TestValue::Red, evidence(EvidenceKind::Observation {
+            location: carol().id(),
+        }, 1.0));
+        speaker.recompute_total_strengths();
+        speaker.recompute_strongest();
+
+        let told = speaker.tell(
+            &mut listener,
+            Claim::Strongest(TestFacet::HairColor),
+            alice().id(),
+            1.0,
+            0.5,
+        );
+        assert_eq!(told, Some(TestValue::Red));
+        assert_eq!(listener.get_strongest_belief(&TestFacet::HairColor), Some(&TestValue::Red));
+        let heard = &listener.facets[&TestFacet::HairColor].values[&TestValue::Red];
+        assert_eq!(heard.total_strength, 0.5);
+        assert!(matches!(
+            &heard.evidence[0].kind,
+            EvidenceKind::Statement { source, .. } if *source == alice().id(),
+        ));
+        let said = &speaker.facets[&TestFacet::HairColor].values[&TestValue::Red];
+        assert_eq!(said.total_strength, 1.5);
+        assert!(matches!(
+            &said.evidence[1].kind,
+            EvidenceKind::Declaration { recipient, .. } if *recipient == bob().id(),
+        ));
+    }
+
+    #[test]
+    fn nothing_to_say_without_a_belief() {
+        let mut speaker = EvidenceModel::<TestTypes>::new(alice(), carol());
+        let mut listener = EvidenceModel::new(bob(), carol());
+        let claim = Claim::Strongest(TestFacet::Job);
+        assert_eq!(speaker.tell(&mut listener, claim, alice().id(), 0.0, 1.0), None);
+        assert!(listener.facets[&TestFacet::Job].values.values().all(|v| v.evidence.is_empty()));
+    }
+
+    #[test]
+    fn lies_about_oneself() {
+        let mut speaker = Mind::<TestTypes>::new(alice());
+        let mut listener = Mind::<TestTypes>::new(bob());
+        let claim = Claim::Lie(TestValue::Smith);
+        let told = speaker.tell(&mut listener, &alice(), claim, carol().id(), 0.0, 2.0);
+        assert_eq!(told, Some(TestValue::Smith));
+        assert!(speaker.models.is_empty());
+        let job = &TestFacet::Job;
+        assert_eq!(listener.get_strongest_belief(&alice(), job), Some(&TestValue::Smith));
+
+        let claim = Claim::Strongest(TestFacet::HairColor);
+        let told = listener.tell(&mut speaker, &alice(), claim, carol().id(), 0.0, 1.0);
+        assert_eq!(told, None);
+        let claim = Claim::Strongest(TestFacet::Job);
+        listener.tell(&mut speaker, &alice(), claim, carol().id(), 0.0, 1.0);
+        let declared = &listener.models[&alice()].facets[&TestFacet::Job].values[&TestValue::Smith];
+        assert!(matches!(declared.evidence[1].kind, EvidenceKind::Declaration { .. }));
+        assert!(speaker.models.is_empty());
+    }
+
+    #[test]
+    fn eavesdroppers_overhear_with_provenance() {
+        let mut speaker = Mind::<TestTypes>::new(alice());
+        let mut listener = Mind::<TestTypes>::new(bob());
+        let mut eavesdropper = Mind::<TestTypes>::new(carol());
+        let dave = TestEntity::new("dave", TestValue::Red, TestValue::Smith);
+        speaker.insert_evidence(&dave, TestValue::Red, evidence(EvidenceKind::Observation {
+            location: dave.id(),
+        }, 1.0));
+
+        let claim = Claim::Strongest(TestFacet::HairColor);
+        let told = speaker.tell_within_earshot(
+            &mut listener,
+            [&mut eavesdropper],
+            &dave,
+            claim,
+            alice().id(),
+            0.0,
+            1.0,
+        );
+        assert_eq!(told, Some(TestValue::Red));
+        let overheard = &eavesdropper.models[&dave].facets[&TestFacet::HairColor].values
+            [&TestValue::Red].evidence[0];
+        assert!(matches!(
+            &overheard.kind,
+            EvidenceKind::Overheard { source, recipient, .. }
+                if *source == alice().id() && *recipient == bob().id(),
+        ));
+        assert!(matches!(overheard.root().kind, EvidenceKind::Observation { .. }));
+        assert_eq!(overheard.relayers(), vec!["alice"]);
+        let hair = &TestFacet::HairColor;
+        assert_eq!(eavesdropper.get_nested_belief(&bob(), &dave, hair), Some(&TestValue::Red));
+    }
+}
@@ -0,0 +1,168 @@
+use super::*;
+
+/// A lightweight handle to an entity stored in an [entity registry](EntityRegistry).
+///
+/// This is meant to be the [entity ID](KnowledgeTrait::EntityId) of a large entity type: each
+/// entity stores the ID it was [registered](EntityRegistry::insert_with) with and returns it from
+/// [`Entity::id`], so evidence refers to it with four bytes instead of a clone of the whole
+/// entity.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, From, Into, Serialize, Deserialize,
+)]
+pub struct EntityId(pub u32);
+
+/// Something that can look up the entity behind an [entity ID](KnowledgeTrait::EntityId).
+///
+/// This is implemented for [entity registries](EntityRegistry), and for slices of entities by
+/// comparing their IDs.
+pub trait EntityResolver<K: KnowledgeTrait> {
+    /// Get the entity with a particular ID, if it's known.
+    fn resolve(&self, id: &K::EntityId) -> Option<&K::Entity>;
+}
+
+impl<K: KnowledgeTrait> EntityResolver<K> for [K::Entity] {
+    fn resolve(&self, id: &K::EntityId) -> Option<&K::Entity> {
+        self.iter().find(|entity| entity.id() == *id)
+    }
+}
+
+/// Storage for entities, handing out an [`EntityId`] for each.
+///
+/// IDs are never reused, so a handle to a removed entity never resolves to a different entity.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "E: Serialize", deserialize = "E: for<'a> Deserialize<'a>"))]
+pub struct EntityRegistry<E> {
+    /// The entities, indexed by ID. Removed entities leave an empty slot.
+    entities: Vec<Option<E>>,
+    /// The number of entities that haven't been removed.
+    len: usize,
+}
+
+impl<E> EntityRegistry<E> {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        EntityRegistry {
+            entities: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Add an entity and get its ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than `u32::MAX` entities have ever been added.
+    pub fn insert(&mut self, entity: E) -> EntityId {
+        self.insert_with(|_| entity)
+    }
+
+    /// Add an entity made from its ID, such as one that stores its own ID, and get its ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than `u32::MAX` entities have ever been added.
+    pub fn insert_with<F: FnOnce(EntityId) -> E>(&mut self, entity: F) -> EntityId {
+        let id = EntityId(u32::try_from(self.entities.len()).expect("Too many entities!"));
+        self.entities.push(Some(entity(id)));
+        self.len += 1;
+        id
+    }
+
+    /// Get the entity with a particular ID, if it exists.
+    pub fn get(&self, id: EntityId) -> Option<&E> {
+        self.entities.get(id.0 as usize)?.as_ref()
+    }
+
+    /// Get the entity with a particular ID mutably, if it exists.
+    pub fn get_mut(&mut self, id: EntityId) -> Option<&mut E> {
+        self.entities.get_mut(id.0 as usize)?.as_mut()
+    }
+
+    /// Remove and return the entity with a particular ID.
+    pub fn remove(&mut self, id: EntityId) -> Option<E> {
+        let entity = self.entities.get_mut(id.0 as usize)?.take();
+        if entity.is_some() {
+            self.len -= 1;
+        }
+        entity
+    }
+
+    /// Get the number of entities.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether there are no entities.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterate over every entity with its ID.
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, &E)> {
+        self.entities
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entity)| Some((EntityId(i as u32), entity.as_ref()?)))
+    }
+}
+
+impl<K: KnowledgeTrait<EntityId = EntityId>> EntityResolver<K> for EntityRegistry<K::Entity> {
+    fn resolve(&self, id: &EntityId) -> Option<&K::Entity> {
+        self.get(*id)
+    }
+}
+
+impl<E> Default for EntityRegistry<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Clone> Clone for EntityRegistry<E> {
+    fn clone(&self) -> Self {
+        Self {
+            entities: self.entities.clone(),
+            len: self.len,
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.entities.clone_from(&source.entities);
+        self.len = source.len;
+    }
+}
+
+impl<E: Debug> Debug for EntityRegistry<E> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_map()
+            .entries(self.iter())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn ids_resolve_until_removed() {
+        let mut registry = EntityRegistry::new();
+        let alice_id = registry.insert(alice());
+        let bob_id = registry.insert(bob());
+        assert_ne!(alice_id, bob_id);
+        assert_eq!(registry.get(bob_id), Some(&bob()));
+        registry.get_mut(bob_id).unwrap().job = TestValue::Farmer;
+        assert_eq!(registry.get(bob_id).unwrap().job, TestValue::Farmer);
+
+        assert_eq!(registry.remove(alice_id), Some(alice()));
+        assert_eq!(registry.remove(alice_id), None);
+        assert_eq!(registry.get(alice_id), None);
+        let carol_id = registry.insert(carol());
+        assert_ne!(carol_id, alice_id);
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.iter().map(|(id, _)| id).collect::<Vec<_>>(), vec![bob_id, carol_id]);
+        assert_eq!(std::mem::size_of::<EntityId>(), 4);
+    }
+}
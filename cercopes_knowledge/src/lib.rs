@@ -1,13 +1,123 @@
 //! placeholder
 
 #![warn(missing_docs)]
+use std::collections::{HashMap, HashSet, hash_map::Entry};
 use std::hash::Hash;
 use std::fmt::{Debug, Formatter, Result as FmtResult};
-use ahash::AHashMap;
-use derive_more::{From, TryInto};
+use derive_more::{From, Into, TryInto};
 use rand::prelude::*;
+use serde::{Serialize, Deserialize};
+use _private::*;
+
+pub use batch::*;
+pub use changes::*;
+pub use consensus::*;
+pub use decay::*;
+pub use dissonance::*;
+pub use error::*;
+pub use forget::*;
+pub use gossip::*;
+pub use handle::*;
+pub use mind::*;
+pub use mutation::*;
+pub use population::*;
+pub use query::*;
+pub use relation::*;
+pub use similarity::*;
+pub use snapshot::*;
+pub use stale::*;
+pub use trust::*;
+
+mod batch;
+mod belief;
+mod changes;
+mod consensus;
+mod decay;
+mod dissonance;
+mod error;
+mod forget;
+mod gossip;
+mod handle;
+mod mind;
+mod mutation;
+mod nested;
+mod population;
+mod provenance;
+mod query;
+mod relation;
+mod similarity;
+mod snapshot;
+mod stale;
+mod trust;
+
+mod _private {
+    #[cfg(not(feature = "send_sync"))]
+    pub trait MaybeSend {}
+
+    #[cfg(not(feature = "send_sync"))]
+    impl<T: ?Sized> MaybeSend for T {}
+
+    #[cfg(feature = "send_sync")]
+    pub trait MaybeSend: Send {}
+
+    #[cfg(feature = "send_sync")]
+    impl<T: ?Sized + Send> MaybeSend for T {}
+
+    #[cfg(not(feature = "send_sync"))]
+    pub trait MaybeSync {}
+
+    #[cfg(not(feature = "send_sync"))]
+    impl<T: ?Sized> MaybeSync for T {}
+
+    #[cfg(feature = "send_sync")]
+    pub trait MaybeSync: Sync {}
+
+    #[cfg(feature = "send_sync")]
+    impl<T: ?Sized + Sync> MaybeSync for T {}
+
+    pub trait MaybeSendSync: MaybeSend + MaybeSync {}
+
+    impl<T: ?Sized + MaybeSend + MaybeSync> MaybeSendSync for T {}
+}
+
+/// The hasher used by every map and set in this crate.
+///
+/// With the `deterministic` feature, this hashes with fixed keys, so that iteration orders (and so
+/// entire simulations with a seeded RNG) are reproducible between runs of the same build.
+/// Otherwise, every map gets random keys.
+#[cfg(feature = "deterministic")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KnowledgeHasher;
+
+#[cfg(feature = "deterministic")]
+impl std::hash::BuildHasher for KnowledgeHasher {
+    type Hasher = ahash::AHasher;
+
+    fn build_hasher(&self) -> ahash::AHasher {
+        ahash::RandomState::with_seeds(0, 0, 0, 0).build_hasher()
+    }
+}
+
+/// The hasher used by every map and set in this crate.
+///
+/// With the `deterministic` feature, this hashes with fixed keys, so that iteration orders (and so
+/// entire simulations with a seeded RNG) are reproducible between runs of the same build.
+/// Otherwise, every map gets random keys.
+#[cfg(not(feature = "deterministic"))]
+pub type KnowledgeHasher = ahash::RandomState;
+
+/// A hash map using the [`KnowledgeHasher`].
+pub type KnowledgeMap<K, V> = HashMap<K, V, KnowledgeHasher>;
+
+/// A hash set using the [`KnowledgeHasher`].
+pub type KnowledgeSet<T> = HashSet<T, KnowledgeHasher>;
+
+pub trait Entity<K: KnowledgeTrait>: Debug + Clone + MaybeSendSync {
+    /// Get the ID that [evidence](Evidence) refers to this entity by.
+    ///
+    /// See [`KnowledgeTrait::EntityId`].
+    fn id(&self) -> K::EntityId;
 
-pub trait Entity<K: KnowledgeTrait>: Debug + Clone {
     /// Get all possible belief facets that can be held for entity.
     ///
     /// For example, a building would have the 'wall color' and 'building type' facets, but
@@ -26,21 +136,92 @@ pub trait Entity<K: KnowledgeTrait>: Debug + Clone {
         self.relevant_facets().contains(facet)
     }
 
+    /// Return whether a facet is relevant to an entity, or an error if that can't be found.
+    ///
+    /// By default, this is [`Entity::is_facet_relevant`].
+    fn try_is_facet_relevant(&self, facet: &K::Facet) -> Result<bool, KnowledgeError> {
+        Ok(self.is_facet_relevant(facet))
+    }
+
     /// Get the true value of a facet, if possible the facet is relevant to the entity.
     ///
     /// For every [relevant facet](Entity::is_facet_relevant), this must return `Some(_)`, and
     /// `None` otherwise.
     fn facet_truth(&self, facet: &K::Facet) -> Option<K::FacetValue>;
+
+    /// Get all possible belief facets for this entity, or an error if they can't be found.
+    ///
+    /// Override this (along with [`Entity::try_facet_truth`]) if looking up facets can fail, such
+    /// as when they come from a script. By default, this is [`Entity::relevant_facets`].
+    fn try_relevant_facets(&self) -> Result<Vec<K::Facet>, KnowledgeError> {
+        Ok(self.relevant_facets())
+    }
+
+    /// Get the true value of a relevant facet, or an error if it can't be found.
+    ///
+    /// By default, this is [`Entity::facet_truth`], with `None` being an error.
+    fn try_facet_truth(&self, facet: &K::Facet) -> Result<K::FacetValue, KnowledgeError> {
+        self.facet_truth(facet).ok_or_else(|| KnowledgeError::missing_truth(self, facet))
+    }
+
+    /// Get all possible belief facets that can be held for the relationship of this entity to
+    /// another entity.
+    ///
+    /// For example, a person would have the 'is dating' and 'owes money to' facets towards another
+    /// person. Relationships are ordered: the facets from `a` to `b` needn't be the same as those
+    /// from `b` to `a`. Like [`Entity::relevant_facets`], __this is assumed to be constant__ for
+    /// each pair of entities.
+    ///
+    /// By default, entities have no relational facets.
+    fn relevant_relations(&self, _other: &K::Entity) -> Vec<K::Facet> {
+        Vec::new()
+    }
+
+    /// Get all possible belief facets for the relationship of this entity to another entity, or
+    /// an error if they can't be found.
+    ///
+    /// By default, this is [`Entity::relevant_relations`].
+    fn try_relevant_relations(&self, other: &K::Entity) -> Result<Vec<K::Facet>, KnowledgeError> {
+        Ok(self.relevant_relations(other))
+    }
+
+    /// Return whether a relational facet is relevant to the relationship of this entity to another
+    /// entity.
+    ///
+    /// This must agree with [`Entity::relevant_relations`].
+    fn is_relation_relevant(&self, other: &K::Entity, facet: &K::Facet) -> bool {
+        self.relevant_relations(other).contains(facet)
+    }
+
+    /// Get the true value of a relational facet of this entity towards another entity, if the
+    /// facet is relevant to the relationship.
+    ///
+    /// For every [relevant relation](Entity::is_relation_relevant), this must return `Some(_)`,
+    /// and `None` otherwise.
+    fn relation_truth(&self, _other: &K::Entity, _facet: &K::Facet) -> Option<K::FacetValue> {
+        None
+    }
+
+    /// Get the true value of a relevant relational facet, or an error if it can't be found.
+    ///
+    /// By default, this is [`Entity::relation_truth`], with `None` being an error.
+    fn try_relation_truth(
+        &self,
+        other: &K::Entity,
+        facet: &K::Facet,
+    ) -> Result<K::FacetValue, KnowledgeError> {
+        self.relation_truth(other, facet).ok_or_else(|| KnowledgeError::missing_truth(self, facet))
+    }
 }
 
-pub trait Facet<K: KnowledgeTrait>: Eq + Hash + Debug + Clone {
+pub trait Facet<K: KnowledgeTrait>: Eq + Hash + Debug + Clone + MaybeSendSync {
     /// Get all possible belief values for this facet.
     ///
     /// This needn't be exhaustive and simply initializes evidence models.
     fn initial_values(&self) -> Vec<K::FacetValue>;
 }
 
-pub trait FacetValue<K: KnowledgeTrait>: Eq + Hash + Debug + Clone {
+pub trait FacetValue<K: KnowledgeTrait>: Eq + Hash + Debug + Clone + MaybeSendSync {
     /// Get the facet of this value.
     fn facet(&self) -> K::Facet;
 
@@ -52,53 +233,136 @@ pub trait FacetValue<K: KnowledgeTrait>: Eq + Hash + Debug + Clone {
     ///
     /// `self` is the belief value that `evidence` supports, and both are guaranteed to be present
     /// in the `model`.
+    ///
+    /// Implementers can delegate to a [mutation strategy](MutationStrategy), such as
+    /// [`RandomValue`].
     fn try_mutate<R: Rng>(
         &self,
         model: &EvidenceModel<K>,
         evidence: &Evidence<K>,
         rng: &mut R,
     ) -> Option<Self>;
+
+    /// Attempt to mutate a piece of evidence like [`FacetValue::try_mutate`], or return an error
+    /// if mutating failed, such as in a scripted callback.
+    ///
+    /// By default, this is [`FacetValue::try_mutate`].
+    fn try_try_mutate<R: Rng>(
+        &self,
+        model: &EvidenceModel<K>,
+        evidence: &Evidence<K>,
+        rng: &mut R,
+    ) -> Result<Option<Self>, KnowledgeError> {
+        Ok(self.try_mutate(model, evidence, rng))
+    }
+}
+
+/// A user-defined kind of evidence, for [`EvidenceKind::Custom`].
+///
+/// For example, a game could add evidence read in a letter, seen in a dream, or deduced. Use `()`
+/// if there are no custom kinds.
+pub trait CustomKind<K: KnowledgeTrait>: Debug + Clone + MaybeSendSync {
+    /// Get the tag of this kind, which tells it apart from other custom kinds when configuring
+    /// behavior per kind. See [`EvidenceKindTag::Custom`].
+    fn tag(&self) -> u32;
+
+    /// Get the ID of the entity that this evidence was stated by, if there was one.
+    ///
+    /// For example, the writer of a letter. See [`Evidence::source`]. By default, custom evidence
+    /// has no source.
+    fn source(&self) -> Option<&K::EntityId> {
+        None
+    }
+}
+
+impl<K: KnowledgeTrait> CustomKind<K> for () {
+    fn tag(&self) -> u32 {
+        0
+    }
 }
 
-pub trait KnowledgeTrait: Sized {
+pub trait KnowledgeTrait: Sized + MaybeSendSync {
     type Facet: Facet<Self>;
 
     type FacetValue: FacetValue<Self>;
 
     type Entity: Entity<Self>;
 
-    type Data: Debug + Clone;
+    /// How [evidence](Evidence) refers to entities, such as the source of a statement.
+    ///
+    /// Every piece of evidence stores its entities this way, so a large entity type should use a
+    /// small handle such as an [`EntityId`] here, and [resolve](EntityResolver) the entity behind
+    /// it when needed. An entity that is cheap to clone can be its own ID.
+    type EntityId: Debug + Clone + Eq + Hash + MaybeSendSync;
+
+    type Data: Debug + Clone + MaybeSendSync;
+
+    /// The user-defined kinds of evidence. See [`EvidenceKind::Custom`].
+    type CustomKind: CustomKind<Self>;
+
+    /// Get the time at which a piece of evidence was gained, if its data records one.
+    ///
+    /// This is used by time-aware systems such as [evidence decay](DecayPolicy). By default,
+    /// evidence has no timestamp.
+    fn evidence_time(_data: &Self::Data) -> Option<f32> {
+        None
+    }
+
+    /// Whether evidence passed on by [telling](EvidenceModel::tell) records the evidence it came
+    /// from.
+    ///
+    /// See [`Evidence::origin`]. This is off by default, since every hop keeps a copy of the
+    /// evidence before it.
+    const TRACK_PROVENANCE: bool = false;
+
+    /// Whether [telling](Mind::tell) records what the speaker and listener now think each other
+    /// believe.
+    ///
+    /// See [`Mind::infer_nested`]. This is off by default, since every telling adds evidence to
+    /// up to two more models.
+    const TRACK_NESTED_BELIEFS: bool = false;
 }
 
 /// The kind of evidence and evidence data.
+///
+/// Entities are referred to by their [IDs](KnowledgeTrait::EntityId).
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K::EntityId: Serialize, K::Data: Serialize, K::CustomKind: Serialize",
+    deserialize = "
+        K::EntityId: for<'a> Deserialize<'a>,
+        K::Data: for<'a> Deserialize<'a>,
+        K::CustomKind: for<'a> Deserialize<'a>,
+    ",
+))]
 pub enum EvidenceKind<K: KnowledgeTrait> {
     /// A statement is something that was said *to the holder*.
     ///
     /// This "statement" could actually be a lie; there is no inherent truth to this evidence kind.
     Statement {
         /// The entity that made the statement.
-        source: K::Entity,
+        source: K::EntityId,
         /// Where the holder was when the statement was made.
-        location: K::Entity,
+        location: K::EntityId,
     },
     /// An overheard statement (or lie).
     Overheard {
         /// The entity that made the statement.
-        source: K::Entity,
+        source: K::EntityId,
         /// The entity that the statement was made to.
-        recipient: K::Entity,
+        recipient: K::EntityId,
         /// Where the holder was when the statement was made.
-        location: K::Entity,
+        location: K::EntityId,
     },
     /// Evidence that was gained via directly observing another entity.
     Observation {
         /// Where the holder was when the entity was observed.
-        location: K::Entity,
+        location: K::EntityId,
     },
     /// Evidence accidentally gained by having been reminded of another entity that is similiar.
     Transference {
         /// The entity that the holder was reminded of.
-        reminded_of: K::Entity,
+        reminded_of: K::EntityId,
     },
     /// Evidence accidentally gained by making probabilistic assumptions about the distribution of
     /// particular attributes in the relevant community.
@@ -112,9 +376,9 @@ pub enum EvidenceKind<K: KnowledgeTrait> {
     /// make the lie seem true to the speaker.
     Lie {
         /// The entity that received the lie.
-        recipient: K::Entity,
+        recipient: K::EntityId,
         /// Where the holder told the lie.
-        location: K::Entity,
+        location: K::EntityId,
     },
     /// Artificial knowledge that was implanted during initialization or by other means.
     Implantation,
@@ -124,9 +388,9 @@ pub enum EvidenceKind<K: KnowledgeTrait> {
     /// that belief.
     Declaration {
         // The entity that the statement was made to.
-        recipient: K::Entity,
+        recipient: K::EntityId,
         /// Where the holder was when the statement was made.
-        location: K::Entity,
+        location: K::EntityId,
     },
     /// Misremembered evidence.
     Mutation {
@@ -136,6 +400,8 @@ pub enum EvidenceKind<K: KnowledgeTrait> {
         /// invariant to mutation.
         previous: Box<Evidence<K>>,
     },
+    /// A user-defined kind of evidence.
+    Custom(K::CustomKind),
 }
 
 impl<K: KnowledgeTrait> Clone for EvidenceKind<K> {
@@ -169,6 +435,7 @@ impl<K: KnowledgeTrait> Clone for EvidenceKind<K> {
             Self::Mutation { previous } => Self::Mutation {
                 previous: previous.clone(),
             },
+            Self::Custom(kind) => Self::Custom(kind.clone()),
         }
     }
 }
@@ -210,11 +477,117 @@ impl<K: KnowledgeTrait> Debug for EvidenceKind<K> {
                 .debug_struct("Mutation")
                 .field("previous", previous)
                 .finish(),
+            Self::Custom(kind) => f.debug_tuple("Custom").field(kind).finish(),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> PartialEq for EvidenceKind<K>
+where
+    K::Data: PartialEq,
+    K::CustomKind: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::Statement { source, location },
+                Self::Statement { source: other_source, location: other_location },
+            ) => source == other_source && location == other_location,
+            (
+                Self::Overheard { source, recipient, location },
+                Self::Overheard {
+                    source: other_source,
+                    recipient: other_recipient,
+                    location: other_location,
+                },
+            ) => {
+                source == other_source
+                    && recipient == other_recipient
+                    && location == other_location
+            },
+            (
+                Self::Observation { location },
+                Self::Observation { location: other_location },
+            ) => location == other_location,
+            (
+                Self::Transference { reminded_of },
+                Self::Transference { reminded_of: other_reminded_of },
+            ) => reminded_of == other_reminded_of,
+            (Self::Confabulation, Self::Confabulation) => true,
+            (
+                Self::Lie { recipient, location },
+                Self::Lie { recipient: other_recipient, location: other_location },
+            ) => recipient == other_recipient && location == other_location,
+            (Self::Implantation, Self::Implantation) => true,
+            (
+                Self::Declaration { recipient, location },
+                Self::Declaration { recipient: other_recipient, location: other_location },
+            ) => recipient == other_recipient && location == other_location,
+            (
+                Self::Mutation { previous },
+                Self::Mutation { previous: other_previous },
+            ) => previous == other_previous,
+            (Self::Custom(kind), Self::Custom(other_kind)) => kind == other_kind,
+            _ => false,
+        }
+    }
+}
+
+/// The kind of a piece of evidence, without any of its data.
+///
+/// This is useful for configuring behavior per [evidence kind](EvidenceKind).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EvidenceKindTag {
+    /// See [`EvidenceKind::Statement`].
+    Statement,
+    /// See [`EvidenceKind::Overheard`].
+    Overheard,
+    /// See [`EvidenceKind::Observation`].
+    Observation,
+    /// See [`EvidenceKind::Transference`].
+    Transference,
+    /// See [`EvidenceKind::Confabulation`].
+    Confabulation,
+    /// See [`EvidenceKind::Lie`].
+    Lie,
+    /// See [`EvidenceKind::Implantation`].
+    Implantation,
+    /// See [`EvidenceKind::Declaration`].
+    Declaration,
+    /// See [`EvidenceKind::Mutation`].
+    Mutation,
+    /// See [`EvidenceKind::Custom`], with the [tag of the custom kind](CustomKind::tag).
+    Custom(u32),
+}
+
+impl<K: KnowledgeTrait> EvidenceKind<K> {
+    /// Get the [tag](EvidenceKindTag) of this evidence kind.
+    pub fn tag(&self) -> EvidenceKindTag {
+        match self {
+            Self::Statement { .. } => EvidenceKindTag::Statement,
+            Self::Overheard { .. } => EvidenceKindTag::Overheard,
+            Self::Observation { .. } => EvidenceKindTag::Observation,
+            Self::Transference { .. } => EvidenceKindTag::Transference,
+            Self::Confabulation => EvidenceKindTag::Confabulation,
+            Self::Lie { .. } => EvidenceKindTag::Lie,
+            Self::Implantation => EvidenceKindTag::Implantation,
+            Self::Declaration { .. } => EvidenceKindTag::Declaration,
+            Self::Mutation { .. } => EvidenceKindTag::Mutation,
+            Self::Custom(kind) => EvidenceKindTag::Custom(kind.tag()),
         }
     }
 }
 
 /// A particular piece of evidence.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K::EntityId: Serialize, K::Data: Serialize, K::CustomKind: Serialize",
+    deserialize = "
+        K::EntityId: for<'a> Deserialize<'a>,
+        K::Data: for<'a> Deserialize<'a>,
+        K::CustomKind: for<'a> Deserialize<'a>,
+    ",
+))]
 pub struct Evidence<K: KnowledgeTrait> {
     /// Any extra data associated with this evidence.
     pub data: K::Data,
@@ -222,9 +595,33 @@ pub struct Evidence<K: KnowledgeTrait> {
     pub kind: EvidenceKind<K>,
     /// How strong this piece of evidence is.
     pub strength: f32,
+    /// How much the holder weighs this piece of evidence, such as by their
+    /// [trust](Trust::weight) in its source.
+    ///
+    /// The evidence counts towards the total strength of its value with its [effective
+    /// strength](Evidence::effective_strength). See [`FacetValueData::reweigh`].
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    /// The speaker's evidence that this evidence was passed on from, if [provenance is
+    /// tracked](KnowledgeTrait::TRACK_PROVENANCE).
+    ///
+    /// See [`Evidence::provenance`].
+    #[serde(default)]
+    pub origin: Option<Box<Evidence<K>>>,
+}
+
+/// Get the weight of evidence saved without one.
+fn default_weight() -> f32 {
+    1.0
 }
 
 impl<K: KnowledgeTrait> Evidence<K> {
+    /// Get how much this evidence counts towards the total strength of its value: its strength
+    /// scaled by its weight.
+    pub fn effective_strength(&self) -> f32 {
+        self.strength * self.weight
+    }
+
     /// Get the "real" kind of the evidence.
     ///
     /// Mutation isn't a real evidence kind and keeps a reference to the original piece of evidence.
@@ -236,6 +633,21 @@ impl<K: KnowledgeTrait> Evidence<K> {
         kind
     }
 
+    /// Get the ID of the entity that this evidence was (originally) stated by, if there was one.
+    ///
+    /// This is the source of a [statement](EvidenceKind::Statement), an [overheard
+    /// statement](EvidenceKind::Overheard) or a [custom kind](CustomKind::source), looking through
+    /// any mutations.
+    pub fn source(&self) -> Option<&K::EntityId> {
+        match self.principal_kind() {
+            EvidenceKind::Statement { source, .. } | EvidenceKind::Overheard { source, .. } => {
+                Some(source)
+            },
+            EvidenceKind::Custom(kind) => kind.source(),
+            _ => None,
+        }
+    }
+
     pub fn mutate(&mut self) {
         self.kind = EvidenceKind::Mutation { previous: Box::new(self.clone()) };
     }
@@ -252,6 +664,8 @@ impl<K: KnowledgeTrait> Clone for Evidence<K> {
             data: self.data.clone(),
             kind: self.kind.clone(),
             strength: self.strength.clone(),
+            weight: self.weight,
+            origin: self.origin.clone(),
         }
     }
 }
@@ -263,42 +677,118 @@ impl<K: KnowledgeTrait> Debug for Evidence<K> {
             .field("time", &self.data)
             .field("kind", &self.kind)
             .field("strength", &self.strength)
+            .field("weight", &self.weight)
+            .field("origin", &self.origin)
             .finish()
     }
 }
 
+impl<K: KnowledgeTrait> PartialEq for Evidence<K>
+where
+    K::Data: PartialEq,
+    K::CustomKind: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+            && self.kind == other.kind
+            && self.strength == other.strength
+            && self.weight == other.weight
+            && self.origin == other.origin
+    }
+}
+
 /// The evidence data for a particular belief value.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K::EntityId: Serialize, K::Data: Serialize, K::CustomKind: Serialize",
+    deserialize = "
+        K::EntityId: for<'a> Deserialize<'a>,
+        K::Data: for<'a> Deserialize<'a>,
+        K::CustomKind: for<'a> Deserialize<'a>,
+    ",
+))]
 pub struct FacetValueData<K: KnowledgeTrait> {
     /// All of the evidence for this value.
-    pub evidence: Vec<Evidence<K>>,
+    evidence: Vec<Evidence<K>>,
     /// The total strength of this value.
-    pub total_strength: f32,
+    total_strength: f32,
 }
 
 impl<K: KnowledgeTrait> FacetValueData<K> {
-    /// Update the `total_strength` field.
+    /// Get all of the evidence for this value.
+    pub fn evidence(&self) -> &[Evidence<K>] {
+        &self.evidence
+    }
+
+    /// Get the total strength of this value.
+    ///
+    /// This is kept up to date as evidence is added and removed.
+    pub fn total_strength(&self) -> f32 {
+        self.total_strength
+    }
+
+    /// Recompute the total strength from scratch.
+    ///
+    /// The total strength is maintained incrementally, so this is only needed as a consistency
+    /// check (such as to correct floating-point drift).
     pub fn recompute_total_strength(&mut self) {
         self.total_strength = 0.0;
 
         for e in self.evidence.iter() {
-            self.total_strength += e.strength;
+            self.total_strength += e.effective_strength();
+        }
+    }
+
+    /// Set the [weight](Evidence::weight) of every piece of evidence, then recompute the total
+    /// strength.
+    ///
+    /// `weight` gives the weight of each piece of evidence, such as [`Trust::weight`]. The weights
+    /// are kept as evidence is added, removed, decayed and mutated.
+    pub fn reweigh<F>(&mut self, weight: F)
+    where
+        F: Fn(&Evidence<K>) -> f32,
+    {
+        for evidence in self.evidence.iter_mut() {
+            evidence.weight = weight(evidence);
         }
+        self.recompute_total_strength();
+    }
+
+    /// Add a piece of evidence, updating the total strength.
+    pub fn push_evidence(&mut self, evidence: Evidence<K>) {
+        self.total_strength += evidence.effective_strength();
+        self.evidence.push(evidence);
     }
 
-    /// Extend some value data with another facet value's data.
+    /// Extend some value data with another facet value's data, updating the total strength.
     ///
-    /// This will leave the other facet value's data "empty" (0 strength, no evidence). This will
-    /// **not** recompute the total strength.
+    /// This will leave the other facet value's data "empty" (0 strength, no evidence).
     pub fn extend(&mut self, other: &mut Self) {
         self.evidence.append(&mut other.evidence);
-        other.total_strength = 0.0;
+        self.total_strength += std::mem::take(&mut other.total_strength);
     }
 
-    /// Remove a particular piece of evidence and return it.
+    /// Remove a particular piece of evidence and return it, updating the total strength.
     ///
-    /// This will **not** recompute the total strength.
+    /// The last piece of evidence takes the place of the removed one.
     pub fn remove_evidence(&mut self, i: usize) -> Evidence<K> {
-        self.evidence.swap_remove(i)
+        let evidence = self.evidence.swap_remove(i);
+        if self.evidence.is_empty() {
+            self.total_strength = 0.0;
+        } else {
+            self.total_strength -= evidence.effective_strength();
+        }
+        evidence
+    }
+
+    /// Modify the evidence for this value directly, then recompute the total strength.
+    pub fn update_evidence<F, R>(&mut self, update: F) -> R
+    where
+        F: FnOnce(&mut Vec<Evidence<K>>) -> R,
+    {
+        let result = update(&mut self.evidence);
+        self.recompute_total_strength();
+        result
     }
 }
 
@@ -336,42 +826,109 @@ impl<K: KnowledgeTrait> Debug for FacetValueData<K> {
 }
 
 /// The evidence supporting a particular facet value.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "
+        K::FacetValue: Serialize,
+        K::EntityId: Serialize,
+        K::Data: Serialize,
+        K::CustomKind: Serialize,
+    ",
+    deserialize = "
+        K::FacetValue: for<'a> Deserialize<'a>,
+        K::EntityId: for<'a> Deserialize<'a>,
+        K::Data: for<'a> Deserialize<'a>,
+        K::CustomKind: for<'a> Deserialize<'a>,
+    ",
+))]
 pub struct FacetData<K: KnowledgeTrait> {
     /// The true value of this facet, regardless of what the holder believes.
     pub truth: K::FacetValue,
     /// The strongest-held belief value of the facet.
-    pub strongest: Option<K::FacetValue>,
+    strongest: Option<K::FacetValue>,
     /// All of the evidence for all of the facet values.
-    pub values: AHashMap<K::FacetValue, FacetValueData<K>>
+    values: KnowledgeMap<K::FacetValue, FacetValueData<K>>
 }
 
 impl<K: KnowledgeTrait> FacetData<K> {
     /// Initialize the facet data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the true value of the facet can't be found. See [`FacetData::try_new`].
     pub fn new(regarding: &K::Entity, facet: &K::Facet) -> Self {
+        or_panic(Self::try_new(regarding, facet))
+    }
+
+    /// Initialize the facet data, or return an error if the true value of the facet can't be
+    /// found.
+    pub fn try_new(regarding: &K::Entity, facet: &K::Facet) -> Result<Self, KnowledgeError> {
+        regarding.try_facet_truth(facet).map(Self::from_truth)
+    }
+
+    /// Initialize the facet data given the true value of the facet.
+    pub fn from_truth(truth: K::FacetValue) -> Self {
         let mut data = FacetData {
-            truth: regarding
-                .facet_truth(facet)
-                .expect("Regarding did not have a true value for a relevant facet!"),
-            strongest: None,
-            values: facet
+            values: truth
+                .facet()
                 .initial_values()
                 .into_iter()
                 .map(|value| (value, Default::default()))
                 .collect(),
+            truth,
+            strongest: None,
         };
         data.recompute_total_strengths();
         data.recompute_strongest();
         data
     }
 
-    /// Recompute all of the total strengths for all values.
+    /// Get the strongest-held belief value of the facet.
+    ///
+    /// This is kept up to date as evidence is added and removed.
+    pub fn strongest(&self) -> Option<&K::FacetValue> {
+        self.strongest.as_ref()
+    }
+
+    /// Get all of the evidence for all of the facet values.
+    pub fn values(&self) -> &KnowledgeMap<K::FacetValue, FacetValueData<K>> {
+        &self.values
+    }
+
+    /// Get the evidence for a particular value, if there is any.
+    pub fn get_value(&self, value: &K::FacetValue) -> Option<&FacetValueData<K>> {
+        self.values.get(value)
+    }
+
+    /// Recompute all of the total strengths for all values from scratch.
+    ///
+    /// See [`FacetValueData::recompute_total_strength`]. Does **NOT** recompute the strongest
+    /// belief.
     pub fn recompute_total_strengths(&mut self) {
         for value in self.values.values_mut() {
             value.recompute_total_strength();
         }
     }
 
-    /// Recompute which belief is the strongest-held one.
+    /// Set the weight of every piece of evidence for all values, then recompute the total
+    /// strengths and the strongest belief.
+    ///
+    /// See [`FacetValueData::reweigh`].
+    pub fn reweigh<F>(&mut self, weight: F)
+    where
+        F: Fn(&Evidence<K>) -> f32,
+    {
+        for value in self.values.values_mut() {
+            value.reweigh(&weight);
+        }
+
+        self.recompute_strongest();
+    }
+
+    /// Recompute which belief is the strongest-held one from scratch.
+    ///
+    /// The strongest belief is maintained incrementally, so this is only needed as a consistency
+    /// check or after recomputing the total strengths.
     pub fn recompute_strongest(&mut self) {
         let mut max: Option<(&K::FacetValue, f32)> = None;
 
@@ -390,32 +947,94 @@ impl<K: KnowledgeTrait> FacetData<K> {
         self.strongest = max.map(|(value, _)| value.clone());
     }
 
+    /// Update the strongest belief after the total strength of a value changed from `old_total`.
+    fn value_changed(&mut self, value: &K::FacetValue, old_total: f32) {
+        let total = self.values.get(value).map_or(0.0, FacetValueData::total_strength);
+        match self.strongest.as_ref() {
+            Some(strongest) if strongest == value => {
+                if total < old_total {
+                    self.recompute_strongest();
+                }
+            },
+            Some(strongest) => {
+                if total > self.values[strongest].total_strength {
+                    self.strongest = Some(value.clone());
+                }
+            },
+            None => {
+                if total > 0.0 {
+                    self.strongest = Some(value.clone());
+                }
+            },
+        }
+    }
+
     /// Get the belief value data (or initialize it if necessary).
-    pub fn get_value_data(&mut self, value: K::FacetValue) -> &mut FacetValueData<K> {
+    pub fn get_value_data(&mut self, value: K::FacetValue) -> &FacetValueData<K> {
         debug_assert_eq!(self.truth.facet(), value.facet());
         self.values.entry(value).or_default()
     }
 
+    /// Insert a piece of [evidence](Evidence) for a value, updating the total strength and the
+    /// strongest belief.
+    pub fn insert_evidence(&mut self, value: K::FacetValue, evidence: Evidence<K>) {
+        self.update_value(value, |data| data.push_evidence(evidence));
+    }
+
+    /// Remove a particular piece of evidence for a value and return it, updating the total
+    /// strength and the strongest belief.
+    ///
+    /// See [`FacetValueData::remove_evidence`].
+    pub fn remove_evidence(&mut self, value: K::FacetValue, i: usize) -> Evidence<K> {
+        self.update_value(value, |data| data.remove_evidence(i))
+    }
+
+    /// Modify the data of a value (initializing it if necessary), then update the strongest
+    /// belief.
+    ///
+    /// The total strength of the value must be kept up to date by `update`, which is the case for
+    /// every method of [`FacetValueData`].
+    pub fn update_value<F, R>(&mut self, value: K::FacetValue, update: F) -> R
+    where
+        F: FnOnce(&mut FacetValueData<K>) -> R,
+    {
+        debug_assert_eq!(self.truth.facet(), value.facet());
+        let data = self.values.entry(value.clone()).or_default();
+        let old_total = data.total_strength;
+        let result = update(data);
+        self.value_changed(&value, old_total);
+        result
+    }
+
     /// Update the true facet value for the regarded entity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the true value can't be found. See [`FacetData::try_update_truth`].
     pub fn update_truth(&mut self, regarding: &K::Entity) {
-        self.truth = regarding
-            .facet_truth(&self.truth.facet())
-            .expect("Regarding did not have a true value for a relevant facet!");
+        or_panic(self.try_update_truth(regarding))
+    }
+
+    /// Update the true facet value for the regarded entity, or return an error if it can't be
+    /// found.
+    ///
+    /// The old truth is kept on an error.
+    pub fn try_update_truth(&mut self, regarding: &K::Entity) -> Result<(), KnowledgeError> {
+        self.truth = regarding.try_facet_truth(&self.truth.facet())?;
+        Ok(())
     }
 
     /// Merge the data from one value into another.
     ///
-    /// Leaves `take_from` empty and with no strength. Does compute `value` and `take_from`
-    /// strengths, but doesn't update strongest.
+    /// Leaves `take_from` empty and with no strength. Updates both strengths and the strongest
+    /// belief.
     pub fn merge_values(&mut self, value: K::FacetValue, take_from: K::FacetValue) {
         if value == take_from {
             return;
         }
 
-        let mut take_from = std::mem::take(self.get_value_data(take_from));
-        let value = self.get_value_data(value);
-        value.extend(&mut take_from);
-        value.recompute_total_strength();
+        let mut taken = self.update_value(take_from, std::mem::take);
+        self.update_value(value, |value| value.extend(&mut taken));
     }
 }
 
@@ -446,42 +1065,67 @@ impl<K: KnowledgeTrait> Debug for FacetData<K> {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K::Facet: Serialize, K::FacetValue: Serialize, K::Entity: Serialize",
+    deserialize = "
+        K::Facet: for<'a> Deserialize<'a>,
+        K::FacetValue: for<'a> Deserialize<'a>,
+        K::Entity: for<'a> Deserialize<'a>,
+    ",
+))]
 pub struct ReflexiveModel<K: KnowledgeTrait> {
     /// The entity that has the mental model.
     pub holder: K::Entity,
     /// All of the facets of the entity.
     ///
     /// For example, I always know my own hair color, no matter what any other person tells me.
-    pub facets: AHashMap<K::Facet, K::FacetValue>,
+    pub facets: KnowledgeMap<K::Facet, K::FacetValue>,
     //id: UniqueId,
 }
 
 impl<K: KnowledgeTrait> ReflexiveModel<K> {
     /// Create and initialize a new reflexive mental model.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the holder's facets can't be found. See [`ReflexiveModel::try_new`].
     pub fn new(holder: K::Entity) -> Self {
-        ReflexiveModel {
+        or_panic(Self::try_new(holder))
+    }
+
+    /// Create and initialize a new reflexive mental model, or return an error if the holder's
+    /// facets can't be found.
+    pub fn try_new(holder: K::Entity) -> Result<Self, KnowledgeError> {
+        Ok(ReflexiveModel {
             facets: holder
-                .relevant_facets()
+                .try_relevant_facets()?
                 .into_iter()
-                .filter_map(|facet| {
-                    let data = holder
-                        .facet_truth(&facet)
-                        .expect("Holder did not have a true value for a relevant facet!");
-                    Some((facet, data))
+                .map(|facet| {
+                    let data = holder.try_facet_truth(&facet)?;
+                    Ok((facet, data))
                 })
-                .collect(),
+                .collect::<Result<_, _>>()?,
             holder,
             //id: unique_u64(),
-        }
+        })
     }
 
     /// Update the true facet values for this model.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a true value can't be found. See [`ReflexiveModel::try_update_truths`].
     pub fn update_truths(&mut self) {
+        or_panic(self.try_update_truths())
+    }
+
+    /// Update the true facet values for this model, stopping at the first that can't be found.
+    pub fn try_update_truths(&mut self) -> Result<(), KnowledgeError> {
         for (facet, value) in self.facets.iter_mut() {
-            *value = self.holder
-                .facet_truth(facet)
-                .expect("Holder did not have a true value for a relevant facet!");
+            *value = self.holder.try_facet_truth(facet)?;
         }
+        Ok(())
     }
 }
 
@@ -510,94 +1154,210 @@ impl<K: KnowledgeTrait> Debug for ReflexiveModel<K> {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "
+        K::Facet: Serialize,
+        K::FacetValue: Serialize,
+        K::Entity: Serialize,
+        K::EntityId: Serialize,
+        K::Data: Serialize,
+        K::CustomKind: Serialize,
+    ",
+    deserialize = "
+        K::Facet: for<'a> Deserialize<'a>,
+        K::FacetValue: for<'a> Deserialize<'a>,
+        K::Entity: for<'a> Deserialize<'a>,
+        K::EntityId: for<'a> Deserialize<'a>,
+        K::Data: for<'a> Deserialize<'a>,
+        K::CustomKind: for<'a> Deserialize<'a>,
+    ",
+))]
 pub struct EvidenceModel<K: KnowledgeTrait> {
     /// The entity that has the mental model.
     pub holder: K::Entity,
     /// The entity that this mental model is about.
     pub regarding: K::Entity,
     /// All of the facets of the regarded entity.
-    pub facets: AHashMap<K::Facet, FacetData<K>>,
+    pub facets: KnowledgeMap<K::Facet, FacetData<K>>,
+    /// The changes to the strongest beliefs that haven't been drained yet, if they're being
+    /// recorded.
+    ///
+    /// See [`EvidenceModel::set_observing`].
+    #[serde(default)]
+    changes: Option<Vec<BeliefChange<K>>>,
+    /// Every facet whose strongest belief was found to be wrong when staleness was last tracked.
+    ///
+    /// See [`EvidenceModel::track_staleness`].
+    #[serde(default)]
+    stale: KnowledgeMap<K::Facet, Staleness<K>>,
 }
 
 impl<K: KnowledgeTrait> EvidenceModel<K> {
     /// Create an evidence-based mental model for an entity regarding another entity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the regarded entity's facets can't be found. See [`EvidenceModel::try_new`].
     pub fn new(holder: K::Entity, regarding: K::Entity) -> Self {
-        EvidenceModel {
+        or_panic(Self::try_new(holder, regarding))
+    }
+
+    /// Create an evidence-based mental model for an entity regarding another entity, or return an
+    /// error if the regarded entity's facets can't be found.
+    pub fn try_new(holder: K::Entity, regarding: K::Entity) -> Result<Self, KnowledgeError> {
+        Ok(EvidenceModel {
             facets: regarding
-                .relevant_facets()
+                .try_relevant_facets()?
                 .into_iter()
                 .map(|facet| {
-                    let data = FacetData::new(&regarding, &facet);
-                    (facet, data)
+                    let data = FacetData::try_new(&regarding, &facet)?;
+                    Ok((facet, data))
                 })
-                .collect(),
+                .collect::<Result<_, _>>()?,
             holder,
             regarding,
-        }
+            changes: None,
+            stale: KnowledgeMap::default(),
+        })
     }
 
     /// Get the facet data (or initialize it) for a particular facet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the facet data must be initialized and the true value of the facet can't be
+    /// found. See [`EvidenceModel::try_get_facet_data`].
     pub fn get_facet_data(&mut self, facet: K::Facet) -> &mut FacetData<K> {
-        debug_assert!(self.regarding.is_facet_relevant(&facet));
-        let regarding = &self.regarding;
-        self.facets
-            .entry(facet)
-            .or_insert_with_key(|facet| FacetData::new(regarding, facet))
+        or_panic(self.try_get_facet_data(facet))
+    }
+
+    /// Get the facet data (or initialize it) for a particular facet, or return an error if it
+    /// must be initialized and the true value of the facet can't be found.
+    pub fn try_get_facet_data(
+        &mut self,
+        facet: K::Facet,
+    ) -> Result<&mut FacetData<K>, KnowledgeError> {
+        debug_assert!(self.regarding.try_is_facet_relevant(&facet) != Ok(false));
+        Ok(match self.facets.entry(facet) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let data = FacetData::try_new(&self.regarding, entry.key())?;
+                entry.insert(data)
+            },
+        })
     }
 
     /// Get the data regarding a particular facet value, initializing it if necessary.
-    pub fn get_value_data(&mut self, value: K::FacetValue) -> &mut FacetValueData<K> {
+    pub fn get_value_data(&mut self, value: K::FacetValue) -> &FacetValueData<K> {
         self.get_facet_data(value.facet()).get_value_data(value)
     }
 
+    /// Get the data regarding a particular facet value, if there is any.
+    pub fn get_value(&self, value: &K::FacetValue) -> Option<&FacetValueData<K>> {
+        self.facets.get(&value.facet())?.get_value(value)
+    }
+
+    /// Recompute all of the total strengths from scratch.
+    ///
+    /// See [`FacetData::recompute_total_strengths`].
     pub fn recompute_total_strengths(&mut self) {
         for data in self.facets.values_mut() {
             data.recompute_total_strengths();
         }
     }
 
+    /// Set the weight of every piece of evidence, then recompute the total strengths and the
+    /// strongest beliefs.
+    ///
+    /// See [`FacetValueData::reweigh`].
+    pub fn reweigh<F>(&mut self, weight: F)
+    where
+        F: Fn(&Evidence<K>) -> f32,
+    {
+        self.update_facets(|data| data.reweigh(&weight));
+    }
+
+    /// Recompute all of the strongest beliefs from scratch.
+    ///
+    /// See [`FacetData::recompute_strongest`].
     pub fn recompute_strongest(&mut self) {
-        for data in self.facets.values_mut() {
-            data.recompute_strongest();
-        }
+        self.update_facets(FacetData::recompute_strongest);
     }
 
     /// Update all of the true facet values in this model.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a true value can't be found. See [`EvidenceModel::try_update_truths`].
     pub fn update_truths(&mut self) {
+        or_panic(self.try_update_truths())
+    }
+
+    /// Update all of the true facet values in this model, stopping at the first that can't be
+    /// found.
+    pub fn try_update_truths(&mut self) -> Result<(), KnowledgeError> {
         for data in self.facets.values_mut() {
-            data.update_truth(&self.regarding);
+            data.try_update_truth(&self.regarding)?;
         }
+        Ok(())
     }
 
     pub fn get_strongest_belief(&self, facet: &K::Facet) -> Option<&K::FacetValue> {
         self.facets.get(facet)?.strongest.as_ref()
     }
 
+    /// Give every piece of evidence a chance to [mutate](FacetValue::try_mutate) into evidence
+    /// for another value.
+    ///
+    /// Updates the total strengths and the strongest beliefs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if mutating fails. See [`EvidenceModel::try_mutate`].
     pub fn mutate<R: Rng>(&mut self, rng: &mut R) {
-        let mut to_mutate = Vec::with_capacity(self.facets.len() * 2);
+        or_panic(self.try_mutate(rng))
+    }
+
+    /// Give every piece of evidence a chance to [mutate](FacetValue::try_try_mutate) into evidence
+    /// for another value, or return an error if mutating fails.
+    ///
+    /// Nothing is mutated if there is an error. Updates the total strengths and the strongest
+    /// beliefs.
+    pub fn try_mutate<R: Rng>(&mut self, rng: &mut R) -> Result<(), KnowledgeError> {
+        let mut to_mutate = Vec::new();
 
         for (facet, facet_data) in self.facets.iter() {
+            let mut mutations = Vec::new();
             for (value, value_data) in facet_data.values.iter() {
                 for (i, evidence) in value_data.evidence.iter().enumerate() {
-                    if let Some(new_value) = value.try_mutate(self, evidence, rng) {
+                    if let Some(new_value) = value.try_try_mutate(self, evidence, rng)? {
                         debug_assert_eq!(*facet, new_value.facet());
-                        to_mutate.push((value.clone(), new_value, i));
+                        mutations.push((value.clone(), new_value, i));
                     }
                 }
             }
+            if !mutations.is_empty() {
+                to_mutate.push((facet.clone(), mutations));
+            }
         }
 
-        for (value, new_value, i) in to_mutate.into_iter().rev() {
-            let evidence = self.get_value_data(value).remove_evidence(i).mutated();
-            self.get_value_data(new_value).evidence.push(evidence);
+        for (facet, mutations) in to_mutate {
+            self.update_facet(facet, |data| {
+                for (value, new_value, i) in mutations.into_iter().rev() {
+                    let evidence = data.remove_evidence(value, i).mutated();
+                    data.insert_evidence(new_value, evidence);
+                }
+            });
         }
+        Ok(())
     }
 
     /// Insert a piece of [evidence](Evidence) into the model.
     ///
-    /// Does **NOT** recompute total strengths nor the strongest beliefs.
+    /// Updates the total strength and the strongest belief it affects.
     pub fn insert_evidence(&mut self, value: K::FacetValue, evidence: Evidence<K>) {
-        self.get_value_data(value).evidence.push(evidence);
+        self.update_facet(value.facet(), |data| data.insert_evidence(value, evidence));
     }
 }
 
@@ -607,6 +1367,8 @@ impl<K: KnowledgeTrait> Clone for EvidenceModel<K> {
             holder: self.holder.clone(),
             regarding: self.regarding.clone(),
             facets: self.facets.clone(),
+            changes: self.changes.clone(),
+            stale: self.stale.clone(),
         }
     }
 
@@ -614,6 +1376,8 @@ impl<K: KnowledgeTrait> Clone for EvidenceModel<K> {
         self.holder.clone_from(&source.holder);
         self.regarding.clone_from(&source.regarding);
         self.facets.clone_from(&source.facets);
+        self.changes.clone_from(&source.changes);
+        self.stale.clone_from(&source.stale);
     }
 }
 
@@ -624,6 +1388,8 @@ impl<K: KnowledgeTrait> Debug for EvidenceModel<K> {
             .field("holder", &self.holder)
             .field("regarding", &self.regarding)
             .field("facets", &self.facets)
+            .field("changes", &self.changes)
+            .field("stale", &self.stale)
             .finish()
     }
 }
@@ -632,7 +1398,25 @@ impl<K: KnowledgeTrait> Debug for EvidenceModel<K> {
 ///
 /// Can either be a [reflexive model](ReflexiveModel) (a mental model about oneself) or an [evidence
 /// model](EvidenceModel) (a mental model about another entity using an evidence-based approach).
-#[derive(From, TryInto)]
+#[derive(From, TryInto, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "
+        K::Facet: Serialize,
+        K::FacetValue: Serialize,
+        K::Entity: Serialize,
+        K::EntityId: Serialize,
+        K::Data: Serialize,
+        K::CustomKind: Serialize,
+    ",
+    deserialize = "
+        K::Facet: for<'a> Deserialize<'a>,
+        K::FacetValue: for<'a> Deserialize<'a>,
+        K::Entity: for<'a> Deserialize<'a>,
+        K::EntityId: for<'a> Deserialize<'a>,
+        K::Data: for<'a> Deserialize<'a>,
+        K::CustomKind: for<'a> Deserialize<'a>,
+    ",
+))]
 pub enum MentalModel<K: KnowledgeTrait> {
     Reflexive(ReflexiveModel<K>),
     Evidence(EvidenceModel<K>),
@@ -682,6 +1466,15 @@ impl<K: KnowledgeTrait> MentalModel<K> {
         }
     }
 
+    /// Update all of the true facet values in this model, stopping at the first that can't be
+    /// found.
+    pub fn try_update_truths(&mut self) -> Result<(), KnowledgeError> {
+        match self {
+            MentalModel::Reflexive(reflexive) => reflexive.try_update_truths(),
+            MentalModel::Evidence(evidence) => evidence.try_update_truths(),
+        }
+    }
+
     pub fn recompute_total_strengths(&mut self) {
         if let MentalModel::Evidence(evidence) = self {
             evidence.recompute_total_strengths();
@@ -703,7 +1496,8 @@ impl<K: KnowledgeTrait> MentalModel<K> {
 
     /// Insert a piece of [evidence](Evidence) into the model.
     ///
-    /// Does **NOT** recompute total strengths nor the strongest beliefs.
+    /// Does nothing for a [reflexive model](ReflexiveModel). See
+    /// [`EvidenceModel::insert_evidence`].
     pub fn insert_evidence(&mut self, value: K::FacetValue, evidence: Evidence<K>) {
         if let MentalModel::Evidence(model) = self {
             model.insert_evidence(value, evidence);
@@ -728,3 +1522,395 @@ impl<K: KnowledgeTrait> Debug for MentalModel<K> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub(crate) enum TestFacet {
+        HairColor,
+        Job,
+        Feeling,
+    }
+
+    impl Facet<TestTypes> for TestFacet {
+        fn initial_values(&self) -> Vec<TestValue> {
+            match self {
+                TestFacet::HairColor => vec![TestValue::Red, TestValue::Green, TestValue::Blue],
+                TestFacet::Job => vec![TestValue::Farmer, TestValue::Smith],
+                TestFacet::Feeling => vec![TestValue::Friend, TestValue::Enemy],
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub(crate) enum TestValue {
+        Red,
+        Green,
+        Blue,
+        Farmer,
+        Smith,
+        Friend,
+        Enemy,
+    }
+
+    impl FacetValue<TestTypes> for TestValue {
+        fn facet(&self) -> TestFacet {
+            match self {
+                TestValue::Red | TestValue::Green | TestValue::Blue => TestFacet::HairColor,
+                TestValue::Farmer | TestValue::Smith => TestFacet::Job,
+                TestValue::Friend | TestValue::Enemy => TestFacet::Feeling,
+            }
+        }
+
+        fn try_mutate<R: Rng>(
+            &self,
+            _model: &EvidenceModel<TestTypes>,
+            _evidence: &Evidence<TestTypes>,
+            rng: &mut R,
+        ) -> Option<Self> {
+            if rng.gen_bool(0.5) {
+                return None;
+            }
+
+            Some(match self {
+                TestValue::Red => TestValue::Green,
+                TestValue::Green => TestValue::Blue,
+                TestValue::Blue => TestValue::Red,
+                TestValue::Farmer => TestValue::Smith,
+                TestValue::Smith => TestValue::Farmer,
+                TestValue::Friend => TestValue::Enemy,
+                TestValue::Enemy => TestValue::Friend,
+            })
+        }
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub(crate) struct TestEntity {
+        pub name: String,
+        pub hair: TestValue,
+        pub job: TestValue,
+    }
+
+    impl PartialEq for TestEntity {
+        fn eq(&self, other: &Self) -> bool {
+            self.name == other.name
+        }
+    }
+
+    impl Eq for TestEntity {}
+
+    impl Hash for TestEntity {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            self.name.hash(state);
+        }
+    }
+
+    impl TestEntity {
+        pub fn new(name: &str, hair: TestValue, job: TestValue) -> Self {
+            TestEntity {
+                name: name.to_string(),
+                hair,
+                job,
+            }
+        }
+    }
+
+    impl Entity<TestTypes> for TestEntity {
+        fn id(&self) -> String {
+            self.name.clone()
+        }
+
+        fn relevant_facets(&self) -> Vec<TestFacet> {
+            vec![TestFacet::HairColor, TestFacet::Job]
+        }
+
+        fn facet_truth(&self, facet: &TestFacet) -> Option<TestValue> {
+            match facet {
+                TestFacet::HairColor => Some(self.hair),
+                TestFacet::Job => Some(self.job),
+                TestFacet::Feeling => None,
+            }
+        }
+
+        fn relevant_relations(&self, _other: &TestEntity) -> Vec<TestFacet> {
+            vec![TestFacet::Feeling]
+        }
+
+        fn relation_truth(&self, other: &TestEntity, facet: &TestFacet) -> Option<TestValue> {
+            match facet {
+                // a broken relation, for testing errors
+                TestFacet::Feeling if self == other => None,
+                TestFacet::Feeling if self.job == other.job => Some(TestValue::Friend),
+                TestFacet::Feeling => Some(TestValue::Enemy),
+                _ => None,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub(crate) enum TestKind {
+        Letter { writer: String },
+        Dream,
+    }
+
+    impl CustomKind<TestTypes> for TestKind {
+        fn tag(&self) -> u32 {
+            match self {
+                TestKind::Letter { .. } => 1,
+                TestKind::Dream => 2,
+            }
+        }
+
+        fn source(&self) -> Option<&String> {
+            match self {
+                TestKind::Letter { writer } => Some(writer),
+                TestKind::Dream => None,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub(crate) struct TestTypes;
+
+    impl KnowledgeTrait for TestTypes {
+        type Facet = TestFacet;
+
+        type FacetValue = TestValue;
+
+        type Entity = TestEntity;
+
+        type EntityId = String;
+
+        type Data = f32;
+
+        type CustomKind = TestKind;
+
+        fn evidence_time(data: &f32) -> Option<f32> {
+            Some(*data)
+        }
+
+        const TRACK_PROVENANCE: bool = true;
+
+        const TRACK_NESTED_BELIEFS: bool = true;
+    }
+
+    pub(crate) fn alice() -> TestEntity {
+        TestEntity::new("alice", TestValue::Blue, TestValue::Farmer)
+    }
+
+    pub(crate) fn bob() -> TestEntity {
+        TestEntity::new("bob", TestValue::Green, TestValue::Smith)
+    }
+
+    pub(crate) fn carol() -> TestEntity {
+        TestEntity::new("carol", TestValue::Red, TestValue::Farmer)
+    }
+
+    pub(crate) fn evidence(kind: EvidenceKind<TestTypes>, strength: f32) -> Evidence<TestTypes> {
+        Evidence {
+            data: 0.0,
+            kind,
+            strength,
+            weight: 1.0,
+            origin: None,
+        }
+    }
+
+    pub(crate) fn timed(evidence: Evidence<TestTypes>, time: f32) -> Evidence<TestTypes> {
+        Evidence {
+            data: time,
+            ..evidence
+        }
+    }
+
+    pub(crate) fn statement(source: TestEntity, strength: f32) -> Evidence<TestTypes> {
+        evidence(EvidenceKind::Statement {
+            source: source.id(),
+            location: alice().id(),
+        }, strength)
+    }
+
+    pub(crate) fn believe(
+        mind: &mut Mind<TestTypes>,
+        regarding: &TestEntity,
+        value: TestValue,
+        strength: f32,
+    ) {
+        let observed = evidence(EvidenceKind::Observation { location: alice().id() }, strength);
+        mind.insert_evidence(regarding, value, observed);
+    }
+
+    fn populated_model() -> EvidenceModel<TestTypes> {
+        let mut model = EvidenceModel::new(alice(), bob());
+        model.insert_evidence(TestValue::Green, evidence(EvidenceKind::Observation {
+            location: carol().id(),
+        }, 2.0));
+        model.insert_evidence(TestValue::Red, evidence(EvidenceKind::Statement {
+            source: carol().id(),
+            location: alice().id(),
+        }, 0.5));
+        model.insert_evidence(TestValue::Blue, evidence(EvidenceKind::Overheard {
+            source: carol().id(),
+            recipient: bob().id(),
+            location: alice().id(),
+        }, 0.25).mutated().mutated());
+        model.insert_evidence(TestValue::Smith, evidence(EvidenceKind::Implantation, 1.0));
+        model.insert_evidence(TestValue::Farmer, evidence(EvidenceKind::Lie {
+            recipient: carol().id(),
+            location: bob().id(),
+        }, 0.75));
+        model.recompute_total_strengths();
+        model.recompute_strongest();
+        model
+    }
+
+    fn round_trip<T: Serialize + for<'a> Deserialize<'a>>(value: &T) -> T {
+        let json = serde_json::to_string(value).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn assert_same_model(left: &EvidenceModel<TestTypes>, right: &EvidenceModel<TestTypes>) {
+        assert_eq!(left.holder, right.holder);
+        assert_eq!(left.regarding, right.regarding);
+        assert_eq!(left.facets.len(), right.facets.len());
+        for (facet, left) in left.facets.iter() {
+            let right = &right.facets[facet];
+            assert_eq!(left.truth, right.truth);
+            assert_eq!(left.strongest, right.strongest);
+            assert_eq!(left.values.len(), right.values.len());
+            for (value, left) in left.values.iter() {
+                let right = &right.values[value];
+                assert_eq!(left.total_strength, right.total_strength);
+                assert_eq!(left.evidence, right.evidence);
+            }
+        }
+    }
+
+    #[test]
+    fn evidence_round_trip() {
+        let original = evidence(EvidenceKind::Statement {
+            source: carol().id(),
+            location: bob().id(),
+        }, 1.5).mutated().mutated();
+        let loaded = round_trip(&original);
+        assert_eq!(format!("{original:?}"), format!("{loaded:?}"));
+        assert!(matches!(
+            loaded.principal_kind(),
+            EvidenceKind::Statement { source, .. } if *source == carol().id(),
+        ));
+    }
+
+    #[test]
+    fn evidence_round_trip_without_self_description() {
+        let passed_on = Evidence {
+            origin: Some(Box::new(evidence(EvidenceKind::Observation {
+                location: bob().id(),
+            }, 1.0))),
+            ..evidence(EvidenceKind::Statement { source: carol().id(), location: bob().id() }, 1.5)
+        };
+        for original in [passed_on.clone(), passed_on.origin.as_deref().unwrap().clone()] {
+            let bytes = bincode::serialize(&original).unwrap();
+            let loaded: Evidence<TestTypes> = bincode::deserialize(&bytes).unwrap();
+            assert_eq!(loaded, original);
+        }
+    }
+
+    #[test]
+    fn custom_kinds_have_tags_and_sources() {
+        let letter = evidence(EvidenceKind::Custom(TestKind::Letter { writer: carol().id() }), 1.0);
+        let dream = evidence(EvidenceKind::Custom(TestKind::Dream), 1.0).mutated();
+        assert_eq!(letter.kind.tag(), EvidenceKindTag::Custom(1));
+        assert_eq!(dream.principal_kind().tag(), EvidenceKindTag::Custom(2));
+        assert_eq!(letter.source(), Some(&carol().id()));
+        assert_eq!(dream.source(), None);
+        assert_eq!(letter.implied_believers(), vec!["carol"]);
+
+        let loaded = round_trip(&letter);
+        assert_eq!(format!("{letter:?}"), format!("{loaded:?}"));
+        let policy = KindHalfLives::new(1.0).with(EvidenceKindTag::Custom(2), f32::INFINITY);
+        assert_eq!(policy.decay(&dream, 1.0), 1.0);
+        assert_eq!(policy.decay(&letter, 1.0), 0.5);
+    }
+
+    #[test]
+    fn evidence_model_round_trip() {
+        let original = populated_model();
+        let loaded = round_trip(&original);
+        assert_same_model(&original, &loaded);
+        assert_eq!(loaded.get_strongest_belief(&TestFacet::HairColor), Some(&TestValue::Green));
+        assert_eq!(loaded.get_strongest_belief(&TestFacet::Job), Some(&TestValue::Smith));
+    }
+
+    #[test]
+    fn mental_model_round_trip() {
+        let reflexive = MentalModel::<TestTypes>::new(alice(), alice());
+        match round_trip(&reflexive) {
+            MentalModel::Reflexive(loaded) => {
+                assert_eq!(loaded.holder, alice());
+                assert_eq!(loaded.facets, ReflexiveModel::<TestTypes>::new(alice()).facets);
+            },
+            MentalModel::Evidence(_) => panic!("expected a reflexive model"),
+        }
+
+        let evidence = MentalModel::Evidence(populated_model());
+        match round_trip(&evidence) {
+            MentalModel::Evidence(loaded) => assert_same_model(&populated_model(), &loaded),
+            MentalModel::Reflexive(_) => panic!("expected an evidence model"),
+        }
+    }
+
+    fn assert_consistent(model: &EvidenceModel<TestTypes>) {
+        let mut recomputed = model.clone();
+        recomputed.recompute_total_strengths();
+        recomputed.recompute_strongest();
+        for (facet, data) in model.facets.iter() {
+            let expected = &recomputed.facets[facet];
+            assert_eq!(data.strongest(), expected.strongest());
+            for (value, value_data) in data.values().iter() {
+                let total = expected.values()[value].total_strength();
+                assert!((value_data.total_strength() - total).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn strengths_are_maintained_incrementally() {
+        let mut model = EvidenceModel::<TestTypes>::new(alice(), bob());
+        model.insert_evidence(TestValue::Red, evidence(EvidenceKind::Implantation, 1.0));
+        assert_eq!(model.get_strongest_belief(&TestFacet::HairColor), Some(&TestValue::Red));
+        model.insert_evidence(TestValue::Green, evidence(EvidenceKind::Implantation, 0.5));
+        model.insert_evidence(TestValue::Green, evidence(EvidenceKind::Implantation, 0.75));
+        assert_eq!(model.get_strongest_belief(&TestFacet::HairColor), Some(&TestValue::Green));
+        assert_consistent(&model);
+
+        let hair = model.get_facet_data(TestFacet::HairColor);
+        hair.remove_evidence(TestValue::Green, 0);
+        assert_eq!(hair.strongest(), Some(&TestValue::Red));
+        hair.merge_values(TestValue::Blue, TestValue::Red);
+        assert_eq!(hair.strongest(), Some(&TestValue::Blue));
+        assert_eq!(hair.values()[&TestValue::Red].total_strength(), 0.0);
+        assert_consistent(&model);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..10 {
+            model.mutate(&mut rng);
+            assert_consistent(&model);
+        }
+    }
+
+    #[cfg(feature = "deterministic")]
+    #[test]
+    fn seeded_mutation_is_reproducible() {
+        let mutate = || {
+            let mut model = populated_model();
+            let mut rng = StdRng::seed_from_u64(7);
+            for _ in 0..10 {
+                model.mutate(&mut rng);
+            }
+            model
+        };
+        assert_same_model(&mutate(), &mutate());
+    }
+}
@@ -0,0 +1,329 @@
+use super::*;
+
+/// All of the mental models held by a single entity.
+///
+/// A mind owns the holder's [reflexive model](ReflexiveModel) and an [evidence
+/// model](EvidenceModel) for every other entity the holder has knowledge of, which are created
+/// lazily as needed, and a [relation model](RelationModel) for every relationship between entities
+/// the holder has knowledge of. It also keeps the holder's [trust](Trust) in sources and their
+/// [nested models](Mind::get_nested) of what other entities believe.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "
+        K::Facet: Serialize,
+        K::FacetValue: Serialize,
+        K::Entity: Serialize + Eq + Hash,
+        K::EntityId: Serialize,
+        K::Data: Serialize,
+        K::CustomKind: Serialize,
+    ",
+    deserialize = "
+        K::Facet: for<'a> Deserialize<'a>,
+        K::FacetValue: for<'a> Deserialize<'a>,
+        K::Entity: for<'a> Deserialize<'a> + Eq + Hash,
+        K::EntityId: for<'a> Deserialize<'a>,
+        K::Data: for<'a> Deserialize<'a>,
+        K::CustomKind: for<'a> Deserialize<'a>,
+    ",
+))]
+pub struct Mind<K: KnowledgeTrait> {
+    /// The holder's model of themselves.
+    pub reflexive: ReflexiveModel<K>,
+    /// The holder's models of other entities, keyed by the regarded entity.
+    pub models: KnowledgeMap<K::Entity, EvidenceModel<K>>,
+    /// The holder's models of relationships, keyed by the entity the relationship is from, then by
+    /// the entity it is to.
+    pub relations: KnowledgeMap<K::Entity, KnowledgeMap<K::Entity, RelationModel<K>>>,
+    /// The holder's models of what other entities believe, keyed by the believer, then by the
+    /// entity the beliefs regard.
+    pub nested: KnowledgeMap<K::Entity, KnowledgeMap<K::Entity, EvidenceModel<K>>>,
+    /// The holder's trust in the sources of statements.
+    pub trust: Trust<K>,
+    /// Whether changes to the strongest beliefs of every model are being recorded.
+    ///
+    /// See [`Mind::set_observing`].
+    #[serde(default)]
+    pub(crate) observing: bool,
+}
+
+impl<K: KnowledgeTrait> Mind<K>
+where
+    K::Entity: Eq + Hash,
+{
+    /// Create a new mind with no knowledge of other entities.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the holder's facets can't be found. See [`Mind::try_new`].
+    pub fn new(holder: K::Entity) -> Self {
+        or_panic(Self::try_new(holder))
+    }
+
+    /// Create a new mind with no knowledge of other entities, or return an error if the holder's
+    /// facets can't be found.
+    pub fn try_new(holder: K::Entity) -> Result<Self, KnowledgeError> {
+        Ok(Mind {
+            reflexive: ReflexiveModel::try_new(holder)?,
+            models: KnowledgeMap::default(),
+            relations: KnowledgeMap::default(),
+            nested: KnowledgeMap::default(),
+            trust: Default::default(),
+            observing: false,
+        })
+    }
+
+    /// Get the entity that holds this mind.
+    pub fn holder(&self) -> &K::Entity {
+        &self.reflexive.holder
+    }
+
+    /// Get the model regarding an entity, if the holder has one.
+    pub fn get_model(&self, regarding: &K::Entity) -> Option<&EvidenceModel<K>> {
+        self.models.get(regarding)
+    }
+
+    /// Get the model regarding an entity, initializing it if necessary.
+    ///
+    /// `regarding` must not be the holder; use the [reflexive model](Mind::reflexive) instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the model must be initialized and the regarded entity's facets can't be found.
+    /// See [`Mind::try_get_model_mut`].
+    pub fn get_model_mut(&mut self, regarding: &K::Entity) -> &mut EvidenceModel<K> {
+        or_panic(self.try_get_model_mut(regarding))
+    }
+
+    /// Get the model regarding an entity, initializing it if necessary, or return an error if it
+    /// must be initialized and the regarded entity's facets can't be found.
+    pub fn try_get_model_mut(
+        &mut self,
+        regarding: &K::Entity,
+    ) -> Result<&mut EvidenceModel<K>, KnowledgeError> {
+        debug_assert!(regarding != self.holder());
+        if !self.models.contains_key(regarding) {
+            let mut model = EvidenceModel::try_new(self.holder().clone(), regarding.clone())?;
+            model.set_observing(self.observing);
+            self.models.insert(regarding.clone(), model);
+        }
+        Ok(self.models.get_mut(regarding).unwrap())
+    }
+
+    /// Remove and return the model regarding an entity.
+    pub fn remove_model(&mut self, regarding: &K::Entity) -> Option<EvidenceModel<K>> {
+        self.models.remove(regarding)
+    }
+
+    /// Get the strongest belief the holder has of a facet of an entity.
+    ///
+    /// If `regarding` is the holder, then this is the truth from the reflexive model.
+    pub fn get_strongest_belief(
+        &self,
+        regarding: &K::Entity,
+        facet: &K::Facet,
+    ) -> Option<&K::FacetValue> {
+        if regarding == self.holder() {
+            self.reflexive.facets.get(facet)
+        } else {
+            self.models.get(regarding)?.get_strongest_belief(facet)
+        }
+    }
+
+    /// Insert a piece of [evidence](Evidence) into the model regarding an entity, initializing it
+    /// if necessary.
+    ///
+    /// See [`EvidenceModel::insert_evidence`].
+    pub fn insert_evidence(
+        &mut self,
+        regarding: &K::Entity,
+        value: K::FacetValue,
+        evidence: Evidence<K>,
+    ) {
+        self.get_model_mut(regarding).insert_evidence(value, evidence);
+    }
+
+    /// Update all of the true facet values in every model, including relation and nested models.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a true value can't be found. See [`Mind::try_update_truths`].
+    pub fn update_truths(&mut self) {
+        or_panic(self.try_update_truths())
+    }
+
+    /// Update all of the true facet values in every model, including relation and nested models,
+    /// stopping at the first that can't be found.
+    pub fn try_update_truths(&mut self) -> Result<(), KnowledgeError> {
+        self.reflexive.try_update_truths()?;
+        for model in self.models.values_mut() {
+            model.try_update_truths()?;
+        }
+        for model in self.relation_models_mut() {
+            model.try_update_truths()?;
+        }
+        for model in self.nested_models_mut() {
+            model.try_update_truths()?;
+        }
+        Ok(())
+    }
+
+    /// Mutate the evidence in every model.
+    ///
+    /// Nested models are mutated too, but relation models aren't. See [`EvidenceModel::mutate`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if mutating fails. See [`Mind::try_mutate`].
+    pub fn mutate<R: Rng>(&mut self, rng: &mut R) {
+        or_panic(self.try_mutate(rng))
+    }
+
+    /// Mutate the evidence in every model, stopping at the first model that fails to mutate.
+    ///
+    /// See [`Mind::mutate`] and [`EvidenceModel::try_mutate`].
+    pub fn try_mutate<R: Rng>(&mut self, rng: &mut R) -> Result<(), KnowledgeError> {
+        for model in self.models.values_mut() {
+            model.try_mutate(rng)?;
+        }
+        for model in self.nested_models_mut() {
+            model.try_mutate(rng)?;
+        }
+        Ok(())
+    }
+
+    /// Recompute the total strengths in every model, including relation and nested models.
+    pub fn recompute_total_strengths(&mut self) {
+        for model in self.models.values_mut() {
+            model.recompute_total_strengths();
+        }
+        for model in self.relation_models_mut() {
+            model.recompute_total_strengths();
+        }
+        for model in self.nested_models_mut() {
+            model.recompute_total_strengths();
+        }
+    }
+
+    /// Weigh the evidence in every model by the holder's [trust](Trust::weight) in its source,
+    /// then recompute the total strengths and the strongest beliefs.
+    ///
+    /// The weights are kept until this is called again, so evidence inserted afterwards counts
+    /// with its own [weight](Evidence::weight).
+    pub fn recompute_trusted_strengths(&mut self) {
+        let trust = &self.trust;
+        for model in self.models.values_mut() {
+            model.reweigh(|evidence| trust.weight(evidence));
+        }
+        for model in self.relations.values_mut().flat_map(KnowledgeMap::values_mut) {
+            model.reweigh(|evidence| trust.weight(evidence));
+        }
+        for model in self.nested.values_mut().flat_map(KnowledgeMap::values_mut) {
+            model.reweigh(|evidence| trust.weight(evidence));
+        }
+    }
+
+    /// Recompute the strongest beliefs in every model, including relation and nested models.
+    pub fn recompute_strongest(&mut self) {
+        for model in self.models.values_mut() {
+            model.recompute_strongest();
+        }
+        for model in self.relation_models_mut() {
+            model.recompute_strongest();
+        }
+        for model in self.nested_models_mut() {
+            model.recompute_strongest();
+        }
+    }
+
+    /// Decay the evidence in every model, including relation and nested models.
+    ///
+    /// See [`EvidenceModel::decay`].
+    pub fn decay<P: DecayPolicy<K>>(&mut self, policy: &P, now: f32, elapsed: f32) {
+        for model in self.models.values_mut() {
+            model.decay(policy, now, elapsed);
+        }
+        for model in self.relation_models_mut() {
+            model.decay(policy, now, elapsed);
+        }
+        for model in self.nested_models_mut() {
+            model.decay(policy, now, elapsed);
+        }
+    }
+
+    /// Rebuild the holder's trust in sources from every model.
+    ///
+    /// See [`Trust::recompute`].
+    pub fn revise_trust(&mut self) {
+        self.trust.recompute(self.models.values());
+    }
+}
+
+impl<K: KnowledgeTrait> Clone for Mind<K> {
+    fn clone(&self) -> Self {
+        Self {
+            reflexive: self.reflexive.clone(),
+            models: self.models.clone(),
+            relations: self.relations.clone(),
+            nested: self.nested.clone(),
+            trust: self.trust.clone(),
+            observing: self.observing,
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.reflexive.clone_from(&source.reflexive);
+        self.models.clone_from(&source.models);
+        self.relations.clone_from(&source.relations);
+        self.nested.clone_from(&source.nested);
+        self.trust.clone_from(&source.trust);
+        self.observing = source.observing;
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for Mind<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("Mind")
+            .field("reflexive", &self.reflexive)
+            .field("models", &self.models)
+            .field("relations", &self.relations)
+            .field("nested", &self.nested)
+            .field("trust", &self.trust)
+            .field("observing", &self.observing)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn models_are_created_lazily() {
+        let mut mind = Mind::<TestTypes>::new(alice());
+        assert!(mind.get_model(&bob()).is_none());
+        let hair = &TestFacet::HairColor;
+        assert_eq!(mind.get_strongest_belief(&alice(), hair), Some(&TestValue::Blue));
+        assert_eq!(mind.get_strongest_belief(&bob(), hair), None);
+
+        let implanted = evidence(EvidenceKind::Implantation, 1.0);
+        mind.insert_evidence(&bob(), TestValue::Red, implanted.clone());
+        mind.insert_evidence(&carol(), TestValue::Smith, implanted);
+        mind.recompute_total_strengths();
+        mind.recompute_strongest();
+        assert_eq!(mind.models.len(), 2);
+        assert_eq!(mind.get_strongest_belief(&bob(), hair), Some(&TestValue::Red));
+        assert_eq!(mind.get_strongest_belief(&carol(), &TestFacet::Job), Some(&TestValue::Smith));
+    }
+
+    #[test]
+    fn update_truths_reaches_every_model() {
+        let mut mind = Mind::<TestTypes>::new(alice());
+        mind.get_model_mut(&bob()).regarding.hair = TestValue::Red;
+        mind.reflexive.holder.job = TestValue::Smith;
+        mind.update_truths();
+        assert_eq!(mind.models[&bob()].facets[&TestFacet::HairColor].truth, TestValue::Red);
+        assert_eq!(mind.reflexive.facets[&TestFacet::Job], TestValue::Smith);
+    }
+}
@@ -0,0 +1,278 @@
+use super::*;
+
+/// A reusable way of misremembering evidence.
+///
+/// Implementers of [`FacetValue::try_mutate`] can delegate to a strategy instead of writing their
+/// own. A strategy is split into the [chance](MutationStrategy::chance) that a piece of evidence
+/// mutates at all and the [choice](MutationStrategy::choose) of what it mutates into, so that
+/// strategies can be combined (see [`ScaledChance`]).
+pub trait MutationStrategy<K: KnowledgeTrait> {
+    /// Get the chance (from `0.0` to `1.0`) that a piece of evidence mutates.
+    fn chance(&self, model: &EvidenceModel<K>, evidence: &Evidence<K>) -> f32;
+
+    /// Choose the value that a piece of evidence for `value` mutates into.
+    ///
+    /// Returns `None` if there's nothing to mutate into.
+    fn choose<R: Rng>(
+        &self,
+        value: &K::FacetValue,
+        model: &EvidenceModel<K>,
+        evidence: &Evidence<K>,
+        rng: &mut R,
+    ) -> Option<K::FacetValue>;
+
+    /// Attempt to mutate a piece of evidence for `value`.
+    ///
+    /// This has the same contract as [`FacetValue::try_mutate`].
+    fn try_mutate<R: Rng>(
+        &self,
+        value: &K::FacetValue,
+        model: &EvidenceModel<K>,
+        evidence: &Evidence<K>,
+        rng: &mut R,
+    ) -> Option<K::FacetValue> {
+        let chance = self.chance(model, evidence);
+        if chance > 0.0 && rng.gen_bool(chance.min(1.0) as f64) {
+            self.choose(value, model, evidence, rng)
+        } else {
+            None
+        }
+    }
+}
+
+/// Get every value of the same facet as `value` that the holder knows of, except `value` itself.
+fn other_values<'a, K: KnowledgeTrait>(
+    value: &'a K::FacetValue,
+    model: &'a EvidenceModel<K>,
+) -> impl Iterator<Item = &'a K::FacetValue> {
+    model.facets
+        .get(&value.facet())
+        .into_iter()
+        .flat_map(|data| data.values().keys())
+        .filter(move |other| *other != value)
+}
+
+/// Mutate into any other known value of the facet, all equally likely.
+///
+/// The known values are those in the holder's [facet data](FacetData), which includes the
+/// [initial values](Facet::initial_values) of the facet.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RandomValue {
+    /// The chance that a piece of evidence mutates.
+    pub chance: f32,
+}
+
+impl<K: KnowledgeTrait> MutationStrategy<K> for RandomValue {
+    fn chance(&self, _model: &EvidenceModel<K>, _evidence: &Evidence<K>) -> f32 {
+        self.chance
+    }
+
+    fn choose<R: Rng>(
+        &self,
+        value: &K::FacetValue,
+        model: &EvidenceModel<K>,
+        _evidence: &Evidence<K>,
+        rng: &mut R,
+    ) -> Option<K::FacetValue> {
+        other_values(value, model).choose(rng).cloned()
+    }
+}
+
+/// Mutate into another known value of the facet, preferring values close to the original.
+///
+/// `distance` measures how different two values are. Each other value is weighted by
+/// `exp(-falloff * distance)`, so a higher `falloff` makes distant values less likely. Values at a
+/// negative or non-finite distance are never chosen.
+#[derive(Clone, Copy)]
+pub struct SimilarValue<F> {
+    /// The chance that a piece of evidence mutates.
+    pub chance: f32,
+    /// How quickly values become less likely as they get more distant.
+    pub falloff: f32,
+    /// The distance between two values of the same facet.
+    pub distance: F,
+}
+
+impl<K, F> MutationStrategy<K> for SimilarValue<F>
+where
+    K: KnowledgeTrait,
+    F: Fn(&K::FacetValue, &K::FacetValue) -> f32,
+{
+    fn chance(&self, _model: &EvidenceModel<K>, _evidence: &Evidence<K>) -> f32 {
+        self.chance
+    }
+
+    fn choose<R: Rng>(
+        &self,
+        value: &K::FacetValue,
+        model: &EvidenceModel<K>,
+        _evidence: &Evidence<K>,
+        rng: &mut R,
+    ) -> Option<K::FacetValue> {
+        let candidates: Vec<_> = other_values(value, model)
+            .filter_map(|other| {
+                let distance = (self.distance)(value, other);
+                if distance.is_finite() && distance >= 0.0 {
+                    Some((other, (-self.falloff * distance).exp()))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        candidates
+            .choose_weighted(rng, |(_, weight)| *weight)
+            .ok()
+            .map(|(other, _)| (*other).clone())
+    }
+}
+
+impl<F> Debug for SimilarValue<F> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("SimilarValue")
+            .field("chance", &self.chance)
+            .field("falloff", &self.falloff)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Scale the chance of another strategy by the age, strength and kind of the evidence.
+///
+/// The chance is multiplied by `1 + per_age * age` and by the factor of the evidence's [principal
+/// kind](Evidence::principal_kind), and divided by `1 + per_strength * strength`. So with positive
+/// rates, old evidence is misremembered more often and strong evidence less often. The age is
+/// measured from the evidence's [timestamp](KnowledgeTrait::evidence_time) to `now`; evidence
+/// without a timestamp has no age.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScaledChance<S> {
+    /// The strategy whose chance is scaled, and which chooses the new value.
+    pub strategy: S,
+    /// The current time.
+    pub now: f32,
+    /// How much more likely evidence becomes to mutate per unit of age.
+    pub per_age: f32,
+    /// How much less likely evidence becomes to mutate per unit of strength.
+    pub per_strength: f32,
+    /// The factor for each evidence kind. Kinds without an entry have a factor of `1.0`.
+    pub kinds: KnowledgeMap<EvidenceKindTag, f32>,
+}
+
+impl<S> ScaledChance<S> {
+    /// Wrap a strategy without scaling its chance.
+    pub fn new(strategy: S) -> Self {
+        ScaledChance {
+            strategy,
+            now: 0.0,
+            per_age: 0.0,
+            per_strength: 0.0,
+            kinds: KnowledgeMap::default(),
+        }
+    }
+
+    /// Scale the chance by the age of the evidence at the time `now`.
+    pub fn with_age(mut self, now: f32, per_age: f32) -> Self {
+        self.now = now;
+        self.per_age = per_age;
+        self
+    }
+
+    /// Scale the chance by the strength of the evidence.
+    pub fn with_strength(mut self, per_strength: f32) -> Self {
+        self.per_strength = per_strength;
+        self
+    }
+
+    /// Set the factor of a particular evidence kind.
+    pub fn with_kind(mut self, kind: EvidenceKindTag, factor: f32) -> Self {
+        self.kinds.insert(kind, factor);
+        self
+    }
+
+    /// Get the factor of a particular evidence kind.
+    pub fn kind_factor(&self, kind: EvidenceKindTag) -> f32 {
+        self.kinds.get(&kind).copied().unwrap_or(1.0)
+    }
+}
+
+impl<K: KnowledgeTrait, S: MutationStrategy<K>> MutationStrategy<K> for ScaledChance<S> {
+    fn chance(&self, model: &EvidenceModel<K>, evidence: &Evidence<K>) -> f32 {
+        let age = K::evidence_time(&evidence.data).map_or(0.0, |time| (self.now - time).max(0.0));
+        let strength = evidence.strength.abs();
+        self.strategy.chance(model, evidence)
+            * self.kind_factor(evidence.principal_kind().tag())
+            * (1.0 + self.per_age * age)
+            / (1.0 + self.per_strength * strength)
+    }
+
+    fn choose<R: Rng>(
+        &self,
+        value: &K::FacetValue,
+        model: &EvidenceModel<K>,
+        evidence: &Evidence<K>,
+        rng: &mut R,
+    ) -> Option<K::FacetValue> {
+        self.strategy.choose(value, model, evidence, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn random_values_are_other_known_values() {
+        let model = EvidenceModel::<TestTypes>::new(alice(), bob());
+        let implanted = evidence(EvidenceKind::Implantation, 1.0);
+        let mut rng = StdRng::seed_from_u64(0);
+        let never = RandomValue { chance: 0.0 };
+        assert_eq!(never.try_mutate(&TestValue::Red, &model, &implanted, &mut rng), None);
+
+        let always = RandomValue { chance: 1.0 };
+        for _ in 0..20 {
+            let mutated = always.try_mutate(&TestValue::Red, &model, &implanted, &mut rng);
+            assert!(matches!(mutated, Some(TestValue::Green | TestValue::Blue)));
+            let mutated = always.try_mutate(&TestValue::Farmer, &model, &implanted, &mut rng);
+            assert_eq!(mutated, Some(TestValue::Smith));
+        }
+    }
+
+    #[test]
+    fn similar_values_follow_the_distance() {
+        let model = EvidenceModel::<TestTypes>::new(alice(), bob());
+        let implanted = evidence(EvidenceKind::Implantation, 1.0);
+        let mut rng = StdRng::seed_from_u64(0);
+        let strategy = SimilarValue {
+            chance: 1.0,
+            falloff: 1.0,
+            distance: |left: &TestValue, right: &TestValue| match (left, right) {
+                (TestValue::Red, TestValue::Green) => 1.0,
+                _ => f32::INFINITY,
+            },
+        };
+        for _ in 0..20 {
+            let mutated = strategy.try_mutate(&TestValue::Red, &model, &implanted, &mut rng);
+            assert_eq!(mutated, Some(TestValue::Green));
+        }
+        assert_eq!(strategy.try_mutate(&TestValue::Blue, &model, &implanted, &mut rng), None);
+    }
+
+    #[test]
+    fn chance_scales_with_age_strength_and_kind() {
+        let model = EvidenceModel::<TestTypes>::new(alice(), bob());
+        let strategy = ScaledChance::new(RandomValue { chance: 0.1 })
+            .with_age(10.0, 0.5)
+            .with_strength(1.0)
+            .with_kind(EvidenceKindTag::Implantation, 0.0);
+
+        let observation = evidence(EvidenceKind::Observation { location: carol().id() }, 1.0);
+        let observed = timed(observation.clone(), 8.0);
+        assert!((strategy.chance(&model, &observed) - 0.1).abs() < 1e-6);
+        let old = timed(observation, 6.0);
+        assert!((strategy.chance(&model, &old) - 0.15).abs() < 1e-6);
+        let implanted = evidence(EvidenceKind::Implantation, 1.0);
+        assert_eq!(strategy.chance(&model, &implanted), 0.0);
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(strategy.try_mutate(&TestValue::Red, &model, &implanted, &mut rng), None);
+    }
+}
@@ -0,0 +1,199 @@
+use super::*;
+
+impl<K: KnowledgeTrait> Evidence<K> {
+    /// Get the ID of every entity that this evidence implies believes in the value it supports.
+    ///
+    /// Whoever [states](EvidenceKind::Statement) something is taken to believe it, and whoever
+    /// hears it (including the recipient of an [overheard](EvidenceKind::Overheard) statement,
+    /// [declaration](EvidenceKind::Declaration) or [lie](EvidenceKind::Lie)) gains evidence for it.
+    /// The [source of a custom kind](CustomKind::source) is taken to believe it too. Looks through
+    /// any mutations.
+    pub fn implied_believers(&self) -> Vec<&K::EntityId> {
+        match self.principal_kind() {
+            EvidenceKind::Statement { source, .. } => vec![source],
+            EvidenceKind::Overheard { source, recipient, .. } => vec![source, recipient],
+            EvidenceKind::Declaration { recipient, .. } | EvidenceKind::Lie { recipient, .. } => {
+                vec![recipient]
+            },
+            EvidenceKind::Custom(kind) => kind.source().into_iter().collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Mind<K>
+where
+    K::Entity: Eq + Hash,
+{
+    /// Get the holder's model of what `believer` believes about `regarding`, if the holder has
+    /// one.
+    pub fn get_nested(
+        &self,
+        believer: &K::Entity,
+        regarding: &K::Entity,
+    ) -> Option<&EvidenceModel<K>> {
+        self.nested.get(believer)?.get(regarding)
+    }
+
+    /// Get the holder's model of what `believer` believes about `regarding`, initializing it if
+    /// necessary.
+    ///
+    /// The [holder](EvidenceModel::holder) of the model is `believer`, and its truths are the
+    /// truths of `regarding`. `believer` must not be the holder or `regarding`, since those
+    /// beliefs are modeled directly.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the model must be initialized and the regarded entity's facets can't be found.
+    /// See [`Mind::try_get_nested_mut`].
+    pub fn get_nested_mut(
+        &mut self,
+        believer: &K::Entity,
+        regarding: &K::Entity,
+    ) -> &mut EvidenceModel<K> {
+        or_panic(self.try_get_nested_mut(believer, regarding))
+    }
+
+    /// Get the holder's model of what `believer` believes about `regarding`, initializing it if
+    /// necessary, or return an error if it must be initialized and the regarded entity's facets
+    /// can't be found.
+    pub fn try_get_nested_mut(
+        &mut self,
+        believer: &K::Entity,
+        regarding: &K::Entity,
+    ) -> Result<&mut EvidenceModel<K>, KnowledgeError> {
+        debug_assert!(believer != self.holder() && believer != regarding);
+        let nested = self.nested.entry(believer.clone()).or_default();
+        if !nested.contains_key(regarding) {
+            let mut model = EvidenceModel::try_new(believer.clone(), regarding.clone())?;
+            model.set_observing(self.observing);
+            nested.insert(regarding.clone(), model);
+        }
+        Ok(nested.get_mut(regarding).unwrap())
+    }
+
+    /// Get every entity that the holder has a model of `believer`'s beliefs about.
+    pub fn nested_of(
+        &self,
+        believer: &K::Entity,
+    ) -> impl Iterator<Item = (&K::Entity, &EvidenceModel<K>)> {
+        self.nested.get(believer).into_iter().flatten()
+    }
+
+    /// Get what the holder thinks `believer` most strongly believes about a facet of `regarding`.
+    pub fn get_nested_belief(
+        &self,
+        believer: &K::Entity,
+        regarding: &K::Entity,
+        facet: &K::Facet,
+    ) -> Option<&K::FacetValue> {
+        self.get_nested(believer, regarding)?.get_strongest_belief(facet)
+    }
+
+    /// Insert a piece of evidence for what `believer` believes about `regarding`, initializing the
+    /// nested model if necessary.
+    pub fn insert_nested_evidence(
+        &mut self,
+        believer: &K::Entity,
+        regarding: &K::Entity,
+        value: K::FacetValue,
+        evidence: Evidence<K>,
+    ) {
+        self.get_nested_mut(believer, regarding).insert_evidence(value, evidence);
+    }
+
+    /// Infer what others believe about `regarding` from a piece of evidence the holder gained.
+    ///
+    /// Each of the evidence's [implied believers](Evidence::implied_believers) (other than the
+    /// holder and `regarding`) that `resolver` knows gains a copy of the evidence in the holder's
+    /// model of their beliefs. This is done when [telling](Mind::tell) if [nested beliefs are
+    /// tracked](KnowledgeTrait::TRACK_NESTED_BELIEFS).
+    pub fn infer_nested<R: EntityResolver<K> + ?Sized>(
+        &mut self,
+        regarding: &K::Entity,
+        value: &K::FacetValue,
+        evidence: &Evidence<K>,
+        resolver: &R,
+    ) {
+        for believer in evidence.implied_believers() {
+            let believer = if let Some(believer) = resolver.resolve(believer) {
+                believer
+            } else {
+                continue;
+            };
+            if believer != self.holder() && believer != regarding {
+                self.insert_nested_evidence(believer, regarding, value.clone(), evidence.clone());
+            }
+        }
+    }
+
+    /// Check whether the holder thinks `believer` knows the truth about a facet of `regarding`.
+    ///
+    /// The truth is what the holder believes themselves: the strongest belief of their model of
+    /// `regarding`, or their own facet if `regarding` is the holder. Returns `None` if the holder
+    /// has no belief of either. If `believer` is `regarding`, they always know the truth.
+    pub fn thinks_knows_truth(
+        &self,
+        believer: &K::Entity,
+        regarding: &K::Entity,
+        facet: &K::Facet,
+    ) -> Option<bool> {
+        let truth = self.get_strongest_belief(regarding, facet)?;
+        if believer == regarding {
+            return Some(true);
+        }
+        let believed = if believer == self.holder() {
+            truth
+        } else {
+            self.get_nested_belief(believer, regarding, facet)?
+        };
+        Some(believed == truth)
+    }
+
+    /// Iterate over every nested model mutably.
+    pub(crate) fn nested_models_mut(&mut self) -> impl Iterator<Item = &mut EvidenceModel<K>> {
+        self.nested.values_mut().flat_map(KnowledgeMap::values_mut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn telling_implies_beliefs() {
+        let dave = TestEntity::new("dave", TestValue::Red, TestValue::Smith);
+        let mut alice_mind = Mind::<TestTypes>::new(alice());
+        let mut bob_mind = Mind::<TestTypes>::new(bob());
+        let hair = TestFacet::HairColor;
+        let job = TestFacet::Job;
+
+        alice_mind.insert_evidence(&dave, TestValue::Red, evidence(EvidenceKind::Observation {
+            location: dave.id(),
+        }, 1.0));
+        assert_eq!(alice_mind.thinks_knows_truth(&bob(), &dave, &hair), None);
+        alice_mind.tell(&mut bob_mind, &dave, Claim::Strongest(hair), alice().id(), 0.0, 1.0);
+        assert_eq!(alice_mind.get_nested_belief(&bob(), &dave, &hair), Some(&TestValue::Red));
+        assert_eq!(alice_mind.thinks_knows_truth(&bob(), &dave, &hair), Some(true));
+        assert_eq!(bob_mind.get_nested_belief(&alice(), &dave, &hair), Some(&TestValue::Red));
+
+        let lie = Claim::Lie(TestValue::Smith);
+        alice_mind.tell(&mut bob_mind, &alice(), lie, dave.id(), 0.0, 1.0);
+        assert_eq!(alice_mind.thinks_knows_truth(&bob(), &alice(), &job), Some(false));
+        assert_eq!(alice_mind.thinks_knows_truth(&alice(), &alice(), &job), Some(true));
+        assert!(bob_mind.get_nested(&alice(), &alice()).is_none());
+        assert_eq!(alice_mind.nested_of(&bob()).count(), 2);
+
+        let overheard = evidence(EvidenceKind::Overheard {
+            source: bob().id(),
+            recipient: carol().id(),
+            location: dave.id(),
+        }, 2.0);
+        assert_eq!(overheard.implied_believers(), vec!["bob", "carol"]);
+        // only the believers that can be resolved are inferred
+        alice_mind.infer_nested(&dave, &TestValue::Green, &overheard, &[carol()][..]);
+        assert_eq!(alice_mind.get_nested_belief(&carol(), &dave, &hair), Some(&TestValue::Green));
+        assert_eq!(alice_mind.get_nested_belief(&bob(), &dave, &hair), Some(&TestValue::Red));
+    }
+}
@@ -0,0 +1,219 @@
+use super::*;
+
+/// How common each facet value is in a community of entities.
+///
+/// This is built up either from the true values of entities or from a holder's beliefs about
+/// them, and is then used to [confabulate](Population::confabulate) assumptions about entities
+/// the holder knows little about.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K::Facet: Serialize, K::FacetValue: Serialize",
+    deserialize = "K::Facet: for<'a> Deserialize<'a>, K::FacetValue: for<'a> Deserialize<'a>",
+))]
+pub struct Population<K: KnowledgeTrait> {
+    /// The (weighted) number of entities with each value, per facet.
+    pub counts: KnowledgeMap<K::Facet, KnowledgeMap<K::FacetValue, f32>>,
+}
+
+impl<K: KnowledgeTrait> Population<K> {
+    /// Create a new population with no members.
+    pub fn new() -> Self {
+        Population {
+            counts: KnowledgeMap::default(),
+        }
+    }
+
+    /// Build a population from the true facet values of some entities.
+    pub fn from_truths<'a>(entities: impl IntoIterator<Item = &'a K::Entity>) -> Self
+    where
+        K: 'a,
+    {
+        let mut population = Self::new();
+        for entity in entities {
+            population.add_truths(entity);
+        }
+        population
+    }
+
+    /// Build a population from a holder's beliefs about some entities.
+    pub fn from_beliefs<'a>(models: impl IntoIterator<Item = &'a EvidenceModel<K>>) -> Self
+    where
+        K: 'a,
+    {
+        let mut population = Self::new();
+        for model in models {
+            population.add_beliefs(model);
+        }
+        population
+    }
+
+    /// Count some value as held by `weight` entities.
+    pub fn add_value(&mut self, value: K::FacetValue, weight: f32) {
+        *self.counts
+            .entry(value.facet())
+            .or_default()
+            .entry(value)
+            .or_default() += weight;
+    }
+
+    /// Count the true facet values of an entity.
+    pub fn add_truths(&mut self, entity: &K::Entity) {
+        for facet in entity.relevant_facets() {
+            if let Some(value) = entity.facet_truth(&facet) {
+                self.add_value(value, 1.0);
+            }
+        }
+    }
+
+    /// Count what a holder believes about an entity.
+    ///
+    /// Each facet counts as one entity, split between the values according to the holder's
+    /// [belief distribution](FacetData::distribution). Facets the holder has no beliefs about
+    /// aren't counted.
+    pub fn add_beliefs(&mut self, model: &EvidenceModel<K>) {
+        for data in model.facets.values() {
+            for (value, probability) in data.distribution() {
+                self.add_value(value.clone(), probability);
+            }
+        }
+    }
+
+    /// Get the share of the population that has a particular value.
+    ///
+    /// Returns `0.0` if nothing is known about the value's facet.
+    pub fn frequency(&self, value: &K::FacetValue) -> f32 {
+        let counts = if let Some(counts) = self.counts.get(&value.facet()) {
+            counts
+        } else {
+            return 0.0;
+        };
+        let total: f32 = counts.values().sum();
+        if total > 0.0 {
+            counts.get(value).copied().unwrap_or_default() / total
+        } else {
+            0.0
+        }
+    }
+
+    /// Get the share of the population with each value of a facet.
+    ///
+    /// Sorted from most to least common. Empty if nothing is known about the facet.
+    pub fn distribution(&self, facet: &K::Facet) -> Vec<(&K::FacetValue, f32)> {
+        let counts = if let Some(counts) = self.counts.get(facet) {
+            counts
+        } else {
+            return Vec::new();
+        };
+        let total: f32 = counts.values().sum();
+        if total <= 0.0 {
+            return Vec::new();
+        }
+
+        let mut distribution: Vec<_> = counts
+            .iter()
+            .filter(|(_, &count)| count > 0.0)
+            .map(|(value, count)| (value, count / total))
+            .collect();
+        distribution.sort_by(|(_, l), (_, r)| r.total_cmp(l));
+        distribution
+    }
+
+    /// Seed a model with [confabulation](EvidenceKind::Confabulation) evidence drawn from this
+    /// population.
+    ///
+    /// For every facet of the model, each value gets a piece of confabulation evidence with
+    /// `strength` scaled by how common that value is.
+    pub fn confabulate(&self, model: &mut EvidenceModel<K>, data: K::Data, strength: f32) {
+        let facets: Vec<_> = model.facets.keys().cloned().collect();
+
+        for facet in facets {
+            let distribution = self.distribution(&facet);
+            if distribution.is_empty() {
+                continue;
+            }
+
+            model.update_facet(facet, |facet_data| {
+                for (value, frequency) in distribution {
+                    facet_data.insert_evidence(value.clone(), Evidence {
+                        data: data.clone(),
+                        kind: EvidenceKind::Confabulation,
+                        strength: strength * frequency,
+                        weight: 1.0,
+                        origin: None,
+                    });
+                }
+            });
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Default for Population<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: KnowledgeTrait> Clone for Population<K> {
+    fn clone(&self) -> Self {
+        Self {
+            counts: self.counts.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.counts.clone_from(&source.counts);
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for Population<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("Population")
+            .field("counts", &self.counts)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn truths_are_counted() {
+        let population = Population::<TestTypes>::from_truths(&[alice(), bob(), carol()]);
+        assert!((population.frequency(&TestValue::Farmer) - 2.0 / 3.0).abs() < 1e-6);
+        assert_eq!(population.frequency(&TestValue::Blue), 1.0 / 3.0);
+        assert_eq!(
+            population.distribution(&TestFacet::Job),
+            vec![(&TestValue::Farmer, 2.0 / 3.0), (&TestValue::Smith, 1.0 / 3.0)],
+        );
+    }
+
+    #[test]
+    fn beliefs_are_counted_by_probability() {
+        let mut model = EvidenceModel::new(alice(), bob());
+        model.insert_evidence(TestValue::Red, evidence(EvidenceKind::Implantation, 3.0));
+        model.insert_evidence(TestValue::Green, evidence(EvidenceKind::Implantation, 1.0));
+        model.recompute_total_strengths();
+        let population = Population::from_beliefs([&model]);
+        assert_eq!(population.frequency(&TestValue::Red), 0.75);
+        assert!(population.distribution(&TestFacet::Job).is_empty());
+    }
+
+    #[test]
+    fn confabulation_follows_the_majority() {
+        let population = Population::<TestTypes>::from_truths(&[alice(), bob(), carol()]);
+        let dave = TestEntity::new("dave", TestValue::Red, TestValue::Smith);
+        let mut model = EvidenceModel::new(alice(), dave);
+        population.confabulate(&mut model, 0.0, 3.0);
+        assert_eq!(model.get_strongest_belief(&TestFacet::Job), Some(&TestValue::Farmer));
+        let job = &model.facets[&TestFacet::Job];
+        assert!((job.values[&TestValue::Farmer].total_strength - 2.0).abs() < 1e-6);
+        assert!(matches!(
+            job.values[&TestValue::Smith].evidence[0].kind,
+            EvidenceKind::Confabulation,
+        ));
+        assert_eq!(job.probability(&TestValue::Farmer), population.frequency(&TestValue::Farmer));
+    }
+}
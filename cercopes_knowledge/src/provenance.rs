@@ -0,0 +1,91 @@
+use super::*;
+
+impl<K: KnowledgeTrait> Evidence<K> {
+    /// Get the speaker's evidence that this evidence was passed on from, if it's known.
+    ///
+    /// See [`KnowledgeTrait::TRACK_PROVENANCE`].
+    pub fn origin(&self) -> Option<&Evidence<K>> {
+        self.origin.as_deref()
+    }
+
+    /// Iterate over the chain of evidence that this evidence was passed on through, starting with
+    /// this evidence and ending with the [root](Evidence::root).
+    ///
+    /// Each piece of evidence was held by the [source](Evidence::source) of the piece before it.
+    pub fn provenance(&self) -> impl Iterator<Item = &Evidence<K>> {
+        std::iter::successors(Some(self), |evidence| evidence.origin())
+    }
+
+    /// Get the evidence at the start of the [provenance](Evidence::provenance) chain.
+    ///
+    /// If the chain is complete, this is how the belief entered the community, such as an
+    /// [observation](EvidenceKind::Observation) or a [lie](EvidenceKind::Lie).
+    pub fn root(&self) -> &Evidence<K> {
+        self.provenance().last().unwrap_or(self)
+    }
+
+    /// Get the ID of every entity that passed this evidence on, nearest first.
+    ///
+    /// For example, if Ann heard from Bob who heard from Cara, then Ann's evidence was relayed by
+    /// Bob and then by Cara.
+    pub fn relayers(&self) -> Vec<&K::EntityId> {
+        self.provenance().filter_map(Evidence::source).collect()
+    }
+}
+
+impl<K: KnowledgeTrait> FacetValueData<K> {
+    /// Get the strongest piece of evidence for this value that the holder didn't gain by saying
+    /// it themselves.
+    ///
+    /// This is what the holder passes on as the [origin](Evidence::origin) when they state this
+    /// value, since [declarations](EvidenceKind::Declaration) and [lies](EvidenceKind::Lie) only
+    /// reinforce beliefs that came from elsewhere.
+    pub fn original_evidence(&self) -> Option<&Evidence<K>> {
+        self.evidence
+            .iter()
+            .filter(|evidence| !matches!(
+                evidence.principal_kind(),
+                EvidenceKind::Declaration { .. } | EvidenceKind::Lie { .. },
+            ))
+            .max_by(|l, r| l.strength.total_cmp(&r.strength))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn gossip_chains_are_traced_to_their_root() {
+        let dave = TestEntity::new("dave", TestValue::Red, TestValue::Smith);
+        let mut alice_mind = Mind::<TestTypes>::new(alice());
+        let mut bob_mind = Mind::<TestTypes>::new(bob());
+        let mut carol_mind = Mind::<TestTypes>::new(carol());
+        let hair = TestFacet::HairColor;
+        let job = TestFacet::Job;
+
+        alice_mind.insert_evidence(&dave, TestValue::Red, evidence(EvidenceKind::Observation {
+            location: dave.id(),
+        }, 1.0));
+        alice_mind.tell(&mut bob_mind, &dave, Claim::Strongest(hair), alice().id(), 0.0, 1.0);
+        bob_mind.tell(&mut carol_mind, &dave, Claim::Strongest(hair), bob().id(), 0.0, 1.0);
+        let heard = &carol_mind.models[&dave].facets[&hair].values()[&TestValue::Red].evidence()[0];
+        assert_eq!(heard.relayers(), vec!["bob", "alice"]);
+        assert_eq!(heard.provenance().count(), 3);
+        assert!(matches!(heard.root().kind, EvidenceKind::Observation { .. }));
+
+        alice_mind.tell(&mut bob_mind, &dave, Claim::Lie(TestValue::Farmer), alice().id(), 0.0, 1.0);
+        bob_mind.tell(&mut carol_mind, &dave, Claim::Strongest(job), bob().id(), 0.0, 1.0);
+        let heard = &carol_mind.models[&dave].facets[&job].values()[&TestValue::Farmer];
+        let heard = &heard.evidence()[0];
+        assert_eq!(heard.relayers(), vec!["bob", "alice"]);
+        assert!(matches!(
+            &heard.root().kind,
+            EvidenceKind::Lie { recipient, .. } if *recipient == bob().id(),
+        ));
+        let told = bob_mind.models[&dave].facets[&job].values()[&TestValue::Farmer].evidence();
+        assert!(matches!(told[1].kind, EvidenceKind::Declaration { .. }));
+        assert!(told[1].origin().is_none());
+    }
+}
@@ -0,0 +1,307 @@
+use super::*;
+
+/// How strongly a holder believes an entity has a particular value, as stored in a [belief
+/// index](BeliefIndex).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct IndexedBelief {
+    /// The total strength of the value.
+    pub strength: f32,
+    /// The probability the holder assigns to the value.
+    ///
+    /// See [`FacetData::probability`].
+    pub probability: f32,
+}
+
+/// A reverse index from facet values to the entities a holder believes have them.
+///
+/// The index is built from a holder's [evidence models](EvidenceModel) and answers [belief
+/// queries](BeliefQuery) without scanning any evidence. It isn't updated automatically: after a
+/// model changes, [reindex](BeliefIndex::reindex) it.
+pub struct BeliefIndex<K: KnowledgeTrait> {
+    /// The entities believed to have each value, with how strongly.
+    values: KnowledgeMap<K::FacetValue, KnowledgeMap<K::Entity, IndexedBelief>>,
+    /// The values indexed for each entity.
+    entities: KnowledgeMap<K::Entity, Vec<K::FacetValue>>,
+}
+
+impl<K: KnowledgeTrait> BeliefIndex<K>
+where
+    K::Entity: Eq + Hash,
+{
+    /// Create an empty index.
+    pub fn new() -> Self {
+        BeliefIndex {
+            values: KnowledgeMap::default(),
+            entities: KnowledgeMap::default(),
+        }
+    }
+
+    /// Build an index from some models.
+    pub fn from_models<'a>(models: impl IntoIterator<Item = &'a EvidenceModel<K>>) -> Self
+    where
+        K: 'a,
+    {
+        let mut index = Self::new();
+        for model in models {
+            index.reindex(model);
+        }
+        index
+    }
+
+    /// Replace everything indexed about the regarded entity of a model with the model's current
+    /// beliefs.
+    ///
+    /// Only values with a positive total strength are indexed.
+    pub fn reindex(&mut self, model: &EvidenceModel<K>) {
+        self.remove(&model.regarding);
+        let mut indexed = Vec::new();
+
+        for data in model.facets.values() {
+            for (value, probability) in data.distribution() {
+                let belief = IndexedBelief {
+                    strength: data.values()[value].total_strength(),
+                    probability,
+                };
+                self.values
+                    .entry(value.clone())
+                    .or_default()
+                    .insert(model.regarding.clone(), belief);
+                indexed.push(value.clone());
+            }
+        }
+
+        if !indexed.is_empty() {
+            self.entities.insert(model.regarding.clone(), indexed);
+        }
+    }
+
+    /// Remove everything indexed about an entity.
+    pub fn remove(&mut self, regarding: &K::Entity) {
+        for value in self.entities.remove(regarding).into_iter().flatten() {
+            if let Some(entities) = self.values.get_mut(&value) {
+                entities.remove(regarding);
+                if entities.is_empty() {
+                    self.values.remove(&value);
+                }
+            }
+        }
+    }
+
+    /// Get how strongly the holder believes an entity has a value, if at all.
+    pub fn get(&self, regarding: &K::Entity, value: &K::FacetValue) -> Option<IndexedBelief> {
+        self.values.get(value)?.get(regarding).copied()
+    }
+
+    /// Get every entity the holder believes has a value, with how strongly.
+    pub fn believed_to_have(
+        &self,
+        value: &K::FacetValue,
+    ) -> impl Iterator<Item = (&K::Entity, &IndexedBelief)> {
+        self.values.get(value).into_iter().flatten()
+    }
+
+    /// Get every entity matching a query, strongest first.
+    ///
+    /// See [`BeliefQuery`] for how entities are scored.
+    pub fn query(&self, query: &BeliefQuery<K>) -> Vec<(&K::Entity, f32)> {
+        let mut matches: Vec<_> = self.evaluate(query).into_iter().collect();
+        matches.sort_by(|(_, l), (_, r)| r.total_cmp(l));
+        matches
+    }
+
+    fn evaluate(&self, query: &BeliefQuery<K>) -> KnowledgeMap<&K::Entity, f32> {
+        match query {
+            BeliefQuery::Value { value, threshold } => self
+                .believed_to_have(value)
+                .filter(|(_, belief)| belief.probability >= *threshold)
+                .map(|(entity, belief)| (entity, belief.strength))
+                .collect(),
+            BeliefQuery::And(queries) => {
+                let mut queries = queries.iter();
+                let mut matches = if let Some(query) = queries.next() {
+                    self.evaluate(query)
+                } else {
+                    return KnowledgeMap::default();
+                };
+                for query in queries {
+                    if matches.is_empty() {
+                        break;
+                    }
+                    let other = self.evaluate(query);
+                    matches.retain(|entity, score| {
+                        if let Some(other) = other.get(entity) {
+                            *score = score.min(*other);
+                            true
+                        } else {
+                            false
+                        }
+                    });
+                }
+                matches
+            },
+            BeliefQuery::Or(queries) => {
+                let mut matches = KnowledgeMap::default();
+                for query in queries {
+                    for (entity, score) in self.evaluate(query) {
+                        let best = matches.entry(entity).or_insert(score);
+                        *best = best.max(score);
+                    }
+                }
+                matches
+            },
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Default for BeliefIndex<K>
+where
+    K::Entity: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: KnowledgeTrait> Clone for BeliefIndex<K> {
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            entities: self.entities.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.values.clone_from(&source.values);
+        self.entities.clone_from(&source.entities);
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for BeliefIndex<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("BeliefIndex")
+            .field("values", &self.values)
+            .field("entities", &self.entities)
+            .finish()
+    }
+}
+
+/// A query over a [belief index](BeliefIndex), such as "who do I think likes jazz?"
+///
+/// Every matching entity gets a score: its total strength for a single value, the lowest score of
+/// its parts for [`BeliefQuery::And`], and the highest score of its parts for [`BeliefQuery::Or`].
+pub enum BeliefQuery<K: KnowledgeTrait> {
+    /// Match entities believed to have a value with a probability of at least `threshold`.
+    Value {
+        /// The value the entities must have.
+        value: K::FacetValue,
+        /// The minimum [probability](FacetData::probability) of the value.
+        threshold: f32,
+    },
+    /// Match entities that match every query. Matches nothing if there are no queries.
+    And(Vec<BeliefQuery<K>>),
+    /// Match entities that match any query.
+    Or(Vec<BeliefQuery<K>>),
+}
+
+impl<K: KnowledgeTrait> BeliefQuery<K> {
+    /// Match entities believed to have a value with a probability of at least `threshold`.
+    pub fn value(value: K::FacetValue, threshold: f32) -> Self {
+        BeliefQuery::Value { value, threshold }
+    }
+
+    /// Match entities that match both this query and `other`.
+    pub fn and(self, other: Self) -> Self {
+        match self {
+            BeliefQuery::And(mut queries) => {
+                queries.push(other);
+                BeliefQuery::And(queries)
+            },
+            query => BeliefQuery::And(vec![query, other]),
+        }
+    }
+
+    /// Match entities that match either this query or `other`.
+    pub fn or(self, other: Self) -> Self {
+        match self {
+            BeliefQuery::Or(mut queries) => {
+                queries.push(other);
+                BeliefQuery::Or(queries)
+            },
+            query => BeliefQuery::Or(vec![query, other]),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Clone for BeliefQuery<K> {
+    fn clone(&self) -> Self {
+        match self {
+            BeliefQuery::Value { value, threshold } => BeliefQuery::Value {
+                value: value.clone(),
+                threshold: *threshold,
+            },
+            BeliefQuery::And(queries) => BeliefQuery::And(queries.clone()),
+            BeliefQuery::Or(queries) => BeliefQuery::Or(queries.clone()),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for BeliefQuery<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            BeliefQuery::Value { value, threshold } => f
+                .debug_struct("Value")
+                .field("value", value)
+                .field("threshold", threshold)
+                .finish(),
+            BeliefQuery::And(queries) => f.debug_tuple("And").field(queries).finish(),
+            BeliefQuery::Or(queries) => f.debug_tuple("Or").field(queries).finish(),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Mind<K>
+where
+    K::Entity: Eq + Hash,
+{
+    /// Build a [belief index](BeliefIndex) over every model of this mind.
+    pub fn belief_index(&self) -> BeliefIndex<K> {
+        BeliefIndex::from_models(self.models.values())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn queries_combine_facets() {
+        let dave = TestEntity::new("dave", TestValue::Red, TestValue::Smith);
+        let mut mind = Mind::<TestTypes>::new(alice());
+        believe(&mut mind, &bob(), TestValue::Red, 2.0);
+        believe(&mut mind, &bob(), TestValue::Smith, 1.0);
+        believe(&mut mind, &carol(), TestValue::Red, 3.0);
+        believe(&mut mind, &carol(), TestValue::Blue, 1.0);
+        believe(&mut mind, &carol(), TestValue::Farmer, 1.0);
+        believe(&mut mind, &dave, TestValue::Red, 1.0);
+        believe(&mut mind, &dave, TestValue::Green, 1.0);
+        believe(&mut mind, &dave, TestValue::Smith, 4.0);
+        let mut index = mind.belief_index();
+
+        let red = BeliefQuery::value(TestValue::Red, 0.6);
+        assert_eq!(index.query(&red), vec![(&carol(), 3.0), (&bob(), 2.0)]);
+        let smith = BeliefQuery::value(TestValue::Smith, 0.5);
+        assert_eq!(index.query(&red.clone().and(smith.clone())), vec![(&bob(), 1.0)]);
+        let either = red.or(smith);
+        assert_eq!(index.query(&either), vec![(&dave, 4.0), (&carol(), 3.0), (&bob(), 2.0)]);
+
+        believe(&mut mind, &carol(), TestValue::Blue, 5.0);
+        index.reindex(&mind.models[&carol()]);
+        assert_eq!(index.query(&either), vec![(&dave, 4.0), (&bob(), 2.0)]);
+        assert_eq!(index.get(&carol(), &TestValue::Blue).unwrap().strength, 6.0);
+        index.remove(&dave);
+        assert_eq!(index.believed_to_have(&TestValue::Smith).count(), 1);
+        assert!(index.query(&BeliefQuery::And(Vec::new())).is_empty());
+    }
+}
@@ -0,0 +1,360 @@
+use super::*;
+
+/// An evidence-based mental model of the relationship of one entity to another.
+///
+/// This is the relational counterpart of an [evidence model](EvidenceModel): instead of the facets
+/// of a single entity, it holds beliefs about the [relational facets](Entity::relevant_relations)
+/// of `from` towards `to`, such as who `from` is dating or owes money to. Relationships are
+/// ordered, so the relationship of `to` to `from` has its own model.
+///
+/// Relation models share their [facet data](FacetData) with evidence models, so they are decayed,
+/// reweighed, [forgotten](RelationModel::forget) and have their truths updated like them. However,
+/// their evidence never mutates, since [mutation](FacetValue::try_mutate) needs an evidence model,
+/// changes to their strongest beliefs aren't [observed](Mind::set_observing), and their staleness
+/// isn't [tracked](Mind::track_staleness).
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "
+        K::Facet: Serialize,
+        K::FacetValue: Serialize,
+        K::Entity: Serialize,
+        K::EntityId: Serialize,
+        K::Data: Serialize,
+        K::CustomKind: Serialize,
+    ",
+    deserialize = "
+        K::Facet: for<'a> Deserialize<'a>,
+        K::FacetValue: for<'a> Deserialize<'a>,
+        K::Entity: for<'a> Deserialize<'a>,
+        K::EntityId: for<'a> Deserialize<'a>,
+        K::Data: for<'a> Deserialize<'a>,
+        K::CustomKind: for<'a> Deserialize<'a>,
+    ",
+))]
+pub struct RelationModel<K: KnowledgeTrait> {
+    /// The entity that has the mental model.
+    pub holder: K::Entity,
+    /// The entity whose relationship this mental model is about.
+    pub from: K::Entity,
+    /// The entity that `from` is related to.
+    pub to: K::Entity,
+    /// All of the relational facets of `from` towards `to`.
+    pub facets: KnowledgeMap<K::Facet, FacetData<K>>,
+}
+
+impl<K: KnowledgeTrait> RelationModel<K> {
+    /// Create an evidence-based mental model for an entity regarding the relationship of `from`
+    /// to `to`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a relation's true value can't be found. See [`RelationModel::try_new`].
+    pub fn new(holder: K::Entity, from: K::Entity, to: K::Entity) -> Self {
+        or_panic(Self::try_new(holder, from, to))
+    }
+
+    /// Create an evidence-based mental model for an entity regarding the relationship of `from`
+    /// to `to`, or return an error if a relation's true value can't be found.
+    pub fn try_new(
+        holder: K::Entity,
+        from: K::Entity,
+        to: K::Entity,
+    ) -> Result<Self, KnowledgeError> {
+        Ok(RelationModel {
+            facets: from
+                .try_relevant_relations(&to)?
+                .into_iter()
+                .map(|facet| {
+                    let data = FacetData::from_truth(from.try_relation_truth(&to, &facet)?);
+                    Ok((facet, data))
+                })
+                .collect::<Result<_, _>>()?,
+            holder,
+            from,
+            to,
+        })
+    }
+
+    /// Get the facet data (or initialize it) for a particular relational facet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the facet data must be initialized and the relation's true value can't be found.
+    /// See [`RelationModel::try_get_facet_data`].
+    pub fn get_facet_data(&mut self, facet: K::Facet) -> &mut FacetData<K> {
+        or_panic(self.try_get_facet_data(facet))
+    }
+
+    /// Get the facet data (or initialize it) for a particular relational facet, or return an
+    /// error if it must be initialized and the relation's true value can't be found.
+    pub fn try_get_facet_data(
+        &mut self,
+        facet: K::Facet,
+    ) -> Result<&mut FacetData<K>, KnowledgeError> {
+        debug_assert!(self.from.is_relation_relevant(&self.to, &facet));
+        Ok(match self.facets.entry(facet) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let truth = self.from.try_relation_truth(&self.to, entry.key())?;
+                entry.insert(FacetData::from_truth(truth))
+            },
+        })
+    }
+
+    /// Get the data regarding a particular facet value, if there is any.
+    pub fn get_value(&self, value: &K::FacetValue) -> Option<&FacetValueData<K>> {
+        self.facets.get(&value.facet())?.get_value(value)
+    }
+
+    /// Get the strongest-held belief value of a relational facet.
+    pub fn get_strongest_belief(&self, facet: &K::Facet) -> Option<&K::FacetValue> {
+        self.facets.get(facet)?.strongest()
+    }
+
+    /// Insert a piece of [evidence](Evidence) into the model.
+    ///
+    /// Updates the total strength and the strongest belief it affects.
+    pub fn insert_evidence(&mut self, value: K::FacetValue, evidence: Evidence<K>) {
+        self.get_facet_data(value.facet()).insert_evidence(value, evidence);
+    }
+
+    /// Recompute all of the total strengths from scratch.
+    ///
+    /// See [`FacetData::recompute_total_strengths`].
+    pub fn recompute_total_strengths(&mut self) {
+        for data in self.facets.values_mut() {
+            data.recompute_total_strengths();
+        }
+    }
+
+    /// Set the weight of every piece of evidence, then recompute the total strengths.
+    ///
+    /// See [`FacetValueData::reweigh`].
+    pub fn reweigh<F>(&mut self, weight: F)
+    where
+        F: Fn(&Evidence<K>) -> f32,
+    {
+        for data in self.facets.values_mut() {
+            data.reweigh(&weight);
+        }
+    }
+
+    /// Recompute all of the strongest beliefs from scratch.
+    ///
+    /// See [`FacetData::recompute_strongest`].
+    pub fn recompute_strongest(&mut self) {
+        for data in self.facets.values_mut() {
+            data.recompute_strongest();
+        }
+    }
+
+    /// Update all of the true relational facet values in this model.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a true value can't be found. See [`RelationModel::try_update_truths`].
+    pub fn update_truths(&mut self) {
+        or_panic(self.try_update_truths())
+    }
+
+    /// Update all of the true relational facet values in this model, stopping at the first that
+    /// can't be found.
+    pub fn try_update_truths(&mut self) -> Result<(), KnowledgeError> {
+        for (facet, data) in self.facets.iter_mut() {
+            data.truth = self.from.try_relation_truth(&self.to, facet)?;
+        }
+        Ok(())
+    }
+
+    /// Decay all of the evidence in this model according to a [decay policy](DecayPolicy).
+    ///
+    /// See [`EvidenceModel::decay`].
+    pub fn decay<P: DecayPolicy<K>>(&mut self, policy: &P, now: f32, elapsed: f32) {
+        for data in self.facets.values_mut() {
+            data.decay(policy, now, elapsed);
+        }
+    }
+
+    /// Forget evidence in this model according to a [forgetting policy](ForgetPolicy).
+    ///
+    /// Budgets are ignored. See [`FacetValueData::forget`].
+    pub fn forget(&mut self, policy: &ForgetPolicy) -> ForgetStats
+    where
+        K::Data: PartialEq,
+        K::CustomKind: PartialEq,
+    {
+        let mut stats = ForgetStats::default();
+        for data in self.facets.values_mut() {
+            let values: Vec<_> = data.values().keys().cloned().collect();
+            for value in values {
+                stats += data.update_value(value, |data| data.forget(policy));
+            }
+        }
+        stats
+    }
+}
+
+impl<K: KnowledgeTrait> Clone for RelationModel<K> {
+    fn clone(&self) -> Self {
+        Self {
+            holder: self.holder.clone(),
+            from: self.from.clone(),
+            to: self.to.clone(),
+            facets: self.facets.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.holder.clone_from(&source.holder);
+        self.from.clone_from(&source.from);
+        self.to.clone_from(&source.to);
+        self.facets.clone_from(&source.facets);
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for RelationModel<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("RelationModel")
+            .field("holder", &self.holder)
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .field("facets", &self.facets)
+            .finish()
+    }
+}
+
+impl<K: KnowledgeTrait> Mind<K>
+where
+    K::Entity: Eq + Hash,
+{
+    /// Get the model regarding the relationship of `from` to `to`, if the holder has one.
+    pub fn get_relation(&self, from: &K::Entity, to: &K::Entity) -> Option<&RelationModel<K>> {
+        self.relations.get(from)?.get(to)
+    }
+
+    /// Get the model regarding the relationship of `from` to `to`, initializing it if necessary.
+    ///
+    /// Unlike the facets of entities, the holder's own relationships are evidence-based too.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the model must be initialized and the relationship's facets or their true values
+    /// can't be found. See [`Mind::try_get_relation_mut`].
+    pub fn get_relation_mut(&mut self, from: &K::Entity, to: &K::Entity) -> &mut RelationModel<K> {
+        or_panic(self.try_get_relation_mut(from, to))
+    }
+
+    /// Get the model regarding the relationship of `from` to `to`, initializing it if necessary,
+    /// or return an error if it must be initialized and the relationship's facets or their true
+    /// values can't be found.
+    pub fn try_get_relation_mut(
+        &mut self,
+        from: &K::Entity,
+        to: &K::Entity,
+    ) -> Result<&mut RelationModel<K>, KnowledgeError> {
+        debug_assert!(from != to);
+        let holder = &self.reflexive.holder;
+        let relations = self.relations.entry(from.clone()).or_default();
+        if !relations.contains_key(to) {
+            let model = RelationModel::try_new(holder.clone(), from.clone(), to.clone())?;
+            relations.insert(to.clone(), model);
+        }
+        Ok(relations.get_mut(to).unwrap())
+    }
+
+    /// Get every relationship from an entity that the holder has a model of.
+    pub fn relations_from(
+        &self,
+        from: &K::Entity,
+    ) -> impl Iterator<Item = (&K::Entity, &RelationModel<K>)> {
+        self.relations.get(from).into_iter().flatten()
+    }
+
+    /// Get the strongest belief the holder has of a relational facet of `from` towards `to`.
+    pub fn get_relation_belief(
+        &self,
+        from: &K::Entity,
+        to: &K::Entity,
+        facet: &K::Facet,
+    ) -> Option<&K::FacetValue> {
+        self.get_relation(from, to)?.get_strongest_belief(facet)
+    }
+
+    /// Insert a piece of [evidence](Evidence) into the model regarding the relationship of `from`
+    /// to `to`, initializing it if necessary.
+    ///
+    /// See [`RelationModel::insert_evidence`].
+    pub fn insert_relation_evidence(
+        &mut self,
+        from: &K::Entity,
+        to: &K::Entity,
+        value: K::FacetValue,
+        evidence: Evidence<K>,
+    ) {
+        self.get_relation_mut(from, to).insert_evidence(value, evidence);
+    }
+
+    /// Iterate over every relationship model the holder has.
+    pub(crate) fn relation_models_mut(&mut self) -> impl Iterator<Item = &mut RelationModel<K>> {
+        self.relations.values_mut().flat_map(KnowledgeMap::values_mut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn relations_are_ordered_pairs() {
+        let mut mind = Mind::<TestTypes>::new(alice());
+        let feeling = &TestFacet::Feeling;
+        assert!(mind.get_relation(&bob(), &carol()).is_none());
+        let model = mind.get_relation_mut(&bob(), &carol());
+        assert_eq!(model.facets[feeling].truth, TestValue::Enemy);
+        assert_eq!(model.get_strongest_belief(feeling), None);
+
+        let observed = evidence(EvidenceKind::Observation { location: alice().id() }, 1.0);
+        mind.insert_relation_evidence(&bob(), &carol(), TestValue::Friend, observed.clone());
+        mind.insert_relation_evidence(&carol(), &bob(), TestValue::Enemy, observed);
+        assert_eq!(mind.get_relation_belief(&bob(), &carol(), feeling), Some(&TestValue::Friend));
+        assert_eq!(mind.get_relation_belief(&carol(), &bob(), feeling), Some(&TestValue::Enemy));
+        assert_eq!(mind.get_relation_belief(&bob(), &alice(), feeling), None);
+        assert_eq!(mind.relations_from(&bob()).count(), 1);
+        assert!(mind.models.is_empty());
+
+        mind.get_relation_mut(&bob(), &carol()).to.job = TestValue::Smith;
+        mind.update_truths();
+        let model = mind.get_relation(&bob(), &carol()).unwrap();
+        assert_eq!(model.facets[feeling].truth, TestValue::Friend);
+    }
+
+    #[test]
+    fn relations_are_forgotten_but_not_mutated_observed_or_tracked() {
+        let mut mind = Mind::<TestTypes>::new(alice());
+        mind.set_observing(true);
+        mind.insert_relation_evidence(&bob(), &carol(), TestValue::Enemy, statement(carol(), 0.1));
+        for _ in 0..8 {
+            let stated = statement(bob(), 1.0);
+            mind.insert_relation_evidence(&bob(), &carol(), TestValue::Enemy, stated);
+        }
+        mind.get_relation_mut(&bob(), &carol()).to.job = TestValue::Smith;
+        mind.update_truths();
+
+        mind.mutate(&mut StdRng::seed_from_u64(0));
+        let feeling = &TestFacet::Feeling;
+        let model = mind.get_relation(&bob(), &carol()).unwrap();
+        assert!(model.facets[feeling].values().values().all(|data| {
+            let mut evidence = data.evidence().iter();
+            evidence.all(|evidence| !matches!(evidence.kind, EvidenceKind::Mutation { .. }))
+        }));
+        assert!(mind.drain_changes().is_empty());
+        assert!(mind.track_staleness(0.0).is_empty());
+
+        let stats = mind.forget(&ForgetPolicy::new().with_min_strength(0.5).with_merging());
+        assert_eq!((stats.merged, stats.pruned), (7, 1));
+        let model = mind.get_relation(&bob(), &carol()).unwrap();
+        assert_eq!(model.get_value(&TestValue::Enemy).unwrap().total_strength(), 8.0);
+    }
+}
@@ -0,0 +1,269 @@
+use super::*;
+
+/// A measure of how similar a holder thinks two entities are.
+pub trait Similarity<K: KnowledgeTrait> {
+    /// Get how similar two entities are according to the holder's models of them.
+    ///
+    /// This ranges from `0.0` (nothing in common) to `1.0` (indistinguishable).
+    fn similarity(&self, left: &EvidenceModel<K>, right: &EvidenceModel<K>) -> f32;
+
+    /// Get how similar an entity the holder just met seems to a known entity.
+    ///
+    /// Unlike [`Similarity::similarity`], the holder may not have any beliefs about `met` yet, so
+    /// this should also go by what the holder can see of it at a glance, such as its [true
+    /// values](FacetData::truth).
+    fn first_impression(&self, met: &EvidenceModel<K>, known: &EvidenceModel<K>) -> f32;
+}
+
+/// Entities are similar if the holder believes they share facet values.
+///
+/// Similarity is the weighted share of facets where both strongest beliefs agree, out of the
+/// facets where the holder has a belief about both entities.
+///
+/// For a [first impression](Similarity::first_impression), the true value of each facet of the met
+/// entity stands in for the holder's belief, if they have none. Facets that can't be seen at a
+/// glance (such as a job) should be given a weight of `0.0` so they don't count, though beliefs
+/// about them are still [transferred](Mind::transfer_similar).
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K::Facet: Serialize",
+    deserialize = "K::Facet: for<'a> Deserialize<'a>",
+))]
+pub struct FacetWeights<K: KnowledgeTrait> {
+    /// The weight of any facet without an entry in `weights`.
+    pub default: f32,
+    /// The weight of each facet.
+    pub weights: KnowledgeMap<K::Facet, f32>,
+}
+
+impl<K: KnowledgeTrait> FacetWeights<K> {
+    /// Create a similarity measure where every facet has the same weight.
+    pub fn new(default: f32) -> Self {
+        FacetWeights {
+            default,
+            weights: KnowledgeMap::default(),
+        }
+    }
+
+    /// Set the weight of a particular facet.
+    pub fn with(mut self, facet: K::Facet, weight: f32) -> Self {
+        self.weights.insert(facet, weight);
+        self
+    }
+
+    /// Get the weight of a particular facet.
+    pub fn weight(&self, facet: &K::Facet) -> f32 {
+        self.weights.get(facet).copied().unwrap_or(self.default)
+    }
+}
+
+impl<K: KnowledgeTrait> FacetWeights<K> {
+    /// Get the weighted share of the compared facets that have the same value.
+    fn compare<'a, I>(&self, facets: I) -> f32
+    where
+        I: Iterator<Item = (&'a K::Facet, Option<&'a K::FacetValue>, Option<&'a K::FacetValue>)>,
+        K: 'a,
+    {
+        let mut shared = 0.0;
+        let mut compared = 0.0;
+
+        for (facet, left, right) in facets {
+            if let (Some(left), Some(right)) = (left, right) {
+                let weight = self.weight(facet);
+                compared += weight;
+                if left == right {
+                    shared += weight;
+                }
+            }
+        }
+
+        if compared > 0.0 {
+            shared / compared
+        } else {
+            0.0
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Similarity<K> for FacetWeights<K> {
+    fn similarity(&self, left: &EvidenceModel<K>, right: &EvidenceModel<K>) -> f32 {
+        self.compare(left.facets.iter().map(|(facet, left)| {
+            (facet, left.strongest(), right.get_strongest_belief(facet))
+        }))
+    }
+
+    fn first_impression(&self, met: &EvidenceModel<K>, known: &EvidenceModel<K>) -> f32 {
+        self.compare(met.facets.iter().map(|(facet, met)| {
+            let seen = met.strongest().unwrap_or(&met.truth);
+            (facet, Some(seen), known.get_strongest_belief(facet))
+        }))
+    }
+}
+
+impl<K: KnowledgeTrait> Default for FacetWeights<K> {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl<K: KnowledgeTrait> Clone for FacetWeights<K> {
+    fn clone(&self) -> Self {
+        Self {
+            default: self.default,
+            weights: self.weights.clone(),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for FacetWeights<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("FacetWeights")
+            .field("default", &self.default)
+            .field("weights", &self.weights)
+            .finish()
+    }
+}
+
+impl<K: KnowledgeTrait> Mind<K>
+where
+    K::Entity: Eq + Hash,
+{
+    /// Get the entities the holder thinks are most similar to an entity, most similar first.
+    ///
+    /// At most `count` entities are returned, and only those with a positive similarity.
+    pub fn most_similar<S: Similarity<K>>(
+        &self,
+        regarding: &K::Entity,
+        similarity: &S,
+        count: usize,
+    ) -> Vec<(&K::Entity, f32)> {
+        self.rank_similar(regarding, count, |model, other| similarity.similarity(model, other))
+    }
+
+    /// Get the known entities with the highest positive `score` against `regarding`, highest
+    /// first.
+    fn rank_similar<F>(
+        &self,
+        regarding: &K::Entity,
+        count: usize,
+        score: F,
+    ) -> Vec<(&K::Entity, f32)>
+    where
+        F: Fn(&EvidenceModel<K>, &EvidenceModel<K>) -> f32,
+    {
+        let model = if let Some(model) = self.models.get(regarding) {
+            model
+        } else {
+            return Vec::new();
+        };
+        let mut similar: Vec<_> = self.models
+            .iter()
+            .filter(|(entity, _)| *entity != regarding)
+            .map(|(entity, other)| (entity, score(model, other)))
+            .filter(|(_, similarity)| *similarity > 0.0)
+            .collect();
+        similar.sort_by(|(_, l), (_, r)| r.total_cmp(l));
+        similar.truncate(count);
+        similar
+    }
+
+    /// Have the holder be reminded of similar entities when they meet an entity.
+    ///
+    /// The `count` known entities that `regarding` makes the strongest [first
+    /// impression](Similarity::first_impression) against each lend
+    /// [transference](EvidenceKind::Transference) evidence to the model of `regarding`: every value
+    /// the holder supports for one of those entities is supported for `regarding` too, with its
+    /// total strength scaled by `weakening` and by how similar the entities seem. This works even
+    /// if the holder knows nothing about `regarding` yet.
+    ///
+    /// Returns the entities that the holder was reminded of, with their similarities.
+    pub fn transfer_similar<S: Similarity<K>>(
+        &mut self,
+        regarding: &K::Entity,
+        similarity: &S,
+        count: usize,
+        weakening: f32,
+        data: K::Data,
+    ) -> Vec<(K::Entity, f32)> {
+        let model = self.get_model_mut(regarding);
+        let facets: KnowledgeSet<_> = model.facets.keys().cloned().collect();
+        let similar: Vec<_> = self
+            .rank_similar(regarding, count, |met, known| similarity.first_impression(met, known))
+            .into_iter()
+            .map(|(entity, similarity)| (entity.clone(), similarity))
+            .collect();
+        let mut transferred = Vec::new();
+
+        for (reminded_of, similarity) in similar.iter() {
+            for (facet, data) in self.models[reminded_of].facets.iter() {
+                if !facets.contains(facet) {
+                    continue;
+                }
+                for (value, value_data) in data.values.iter() {
+                    if value_data.total_strength > 0.0 {
+                        let strength = value_data.total_strength * similarity * weakening;
+                        transferred.push((value.clone(), reminded_of.clone(), strength));
+                    }
+                }
+            }
+        }
+
+        let model = self.get_model_mut(regarding);
+        for (value, reminded_of, strength) in transferred {
+            model.insert_evidence(value, Evidence {
+                data: data.clone(),
+                kind: EvidenceKind::Transference { reminded_of: reminded_of.id() },
+                strength,
+                weight: 1.0,
+                origin: None,
+            });
+        }
+
+        similar
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn weighted_shared_beliefs() {
+        let mut mind = Mind::<TestTypes>::new(alice());
+        believe(&mut mind, &bob(), TestValue::Red, 1.0);
+        believe(&mut mind, &bob(), TestValue::Smith, 1.0);
+        believe(&mut mind, &carol(), TestValue::Red, 1.0);
+        believe(&mut mind, &carol(), TestValue::Farmer, 1.0);
+        let weights = FacetWeights::new(1.0).with(TestFacet::HairColor, 3.0);
+        let similarity = weights.similarity(&mind.models[&bob()], &mind.models[&carol()]);
+        assert_eq!(similarity, 0.75);
+        let unrelated = EvidenceModel::new(alice(), carol());
+        assert_eq!(weights.similarity(&mind.models[&bob()], &unrelated), 0.0);
+    }
+
+    #[test]
+    fn meeting_transfers_from_similar_entities() {
+        let dave = TestEntity::new("dave", TestValue::Red, TestValue::Smith);
+        let mut mind = Mind::<TestTypes>::new(alice());
+        believe(&mut mind, &bob(), TestValue::Red, 1.0);
+        believe(&mut mind, &bob(), TestValue::Smith, 1.0);
+        believe(&mut mind, &carol(), TestValue::Blue, 1.0);
+        believe(&mut mind, &carol(), TestValue::Farmer, 1.0);
+
+        // only hair color can be seen at a glance
+        let weights = FacetWeights::new(1.0).with(TestFacet::Job, 0.0);
+        let reminded = mind.transfer_similar(&dave, &weights, 3, 0.5, 0.0);
+        assert_eq!(reminded, vec![(bob(), 1.0)]);
+        let model = &mind.models[&dave];
+        assert_eq!(model.get_strongest_belief(&TestFacet::HairColor), Some(&TestValue::Red));
+        assert_eq!(model.get_strongest_belief(&TestFacet::Job), Some(&TestValue::Smith));
+        let job = &model.facets[&TestFacet::Job].values[&TestValue::Smith];
+        assert_eq!(job.total_strength, 0.5);
+        assert!(matches!(
+            &job.evidence[0].kind,
+            EvidenceKind::Transference { reminded_of } if *reminded_of == bob().id(),
+        ));
+    }
+}
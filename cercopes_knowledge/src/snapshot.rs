@@ -0,0 +1,524 @@
+use super::*;
+
+/// A copy of a mental model at a moment in time, to [diff](ModelSnapshot::diff) against later.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "
+        K::Facet: Serialize,
+        K::FacetValue: Serialize,
+        K::Entity: Serialize,
+        K::EntityId: Serialize,
+        K::Data: Serialize,
+        K::CustomKind: Serialize,
+    ",
+    deserialize = "
+        K::Facet: for<'a> Deserialize<'a>,
+        K::FacetValue: for<'a> Deserialize<'a>,
+        K::Entity: for<'a> Deserialize<'a>,
+        K::EntityId: for<'a> Deserialize<'a>,
+        K::Data: for<'a> Deserialize<'a>,
+        K::CustomKind: for<'a> Deserialize<'a>,
+    ",
+))]
+pub struct ModelSnapshot<K: KnowledgeTrait> {
+    /// When the snapshot was taken, such as the current simulation time.
+    pub time: f32,
+    /// The model as it was.
+    pub model: MentalModel<K>,
+}
+
+impl<K: KnowledgeTrait> ModelSnapshot<K> {
+    /// Compute what changed between this snapshot and a later one of the same model.
+    pub fn diff(&self, later: &ModelSnapshot<K>) -> ModelDiff<K>
+    where
+        K::Data: PartialEq,
+        K::CustomKind: PartialEq,
+    {
+        ModelDiff::between(&self.model, &later.model, self.time, later.time)
+    }
+}
+
+impl<K: KnowledgeTrait> Clone for ModelSnapshot<K> {
+    fn clone(&self) -> Self {
+        Self {
+            time: self.time,
+            model: self.model.clone(),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for ModelSnapshot<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("ModelSnapshot")
+            .field("time", &self.time)
+            .field("model", &self.model)
+            .finish()
+    }
+}
+
+impl<K: KnowledgeTrait> EvidenceModel<K> {
+    /// Take a [snapshot](ModelSnapshot) of this model at the time `time`.
+    ///
+    /// Recorded [changes](EvidenceModel::drain_changes) aren't included.
+    pub fn snapshot(&self, time: f32) -> ModelSnapshot<K> {
+        let model = EvidenceModel {
+            holder: self.holder.clone(),
+            regarding: self.regarding.clone(),
+            facets: self.facets.clone(),
+            changes: None,
+            stale: self.stale.clone(),
+        };
+        ModelSnapshot {
+            time,
+            model: model.into(),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> MentalModel<K> {
+    /// Take a [snapshot](ModelSnapshot) of this model at the time `time`.
+    pub fn snapshot(&self, time: f32) -> ModelSnapshot<K> {
+        match self {
+            MentalModel::Reflexive(reflexive) => ModelSnapshot {
+                time,
+                model: reflexive.clone().into(),
+            },
+            MentalModel::Evidence(evidence) => evidence.snapshot(time),
+        }
+    }
+}
+
+/// A piece of evidence that mutated from supporting one value into supporting another.
+pub struct EvidenceMutation<K: KnowledgeTrait> {
+    /// The value the evidence used to support.
+    pub from: K::FacetValue,
+    /// The value the evidence supports now.
+    pub to: K::FacetValue,
+    /// The evidence as it is now.
+    pub evidence: Evidence<K>,
+}
+
+impl<K: KnowledgeTrait> Clone for EvidenceMutation<K> {
+    fn clone(&self) -> Self {
+        Self {
+            from: self.from.clone(),
+            to: self.to.clone(),
+            evidence: self.evidence.clone(),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for EvidenceMutation<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("EvidenceMutation")
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .field("evidence", &self.evidence)
+            .finish()
+    }
+}
+
+/// What changed about a single facet value.
+pub struct ValueDiff<K: KnowledgeTrait> {
+    /// The value.
+    pub value: K::FacetValue,
+    /// The evidence for the value that is new, other than mutations.
+    pub added: Vec<Evidence<K>>,
+    /// The evidence for the value that is gone, other than mutations.
+    pub removed: Vec<Evidence<K>>,
+    /// The total strength of the value before.
+    pub old_strength: f32,
+    /// The total strength of the value after.
+    pub new_strength: f32,
+}
+
+impl<K: KnowledgeTrait> ValueDiff<K> {
+    /// Get how much the total strength of the value changed.
+    pub fn strength_delta(&self) -> f32 {
+        self.new_strength - self.old_strength
+    }
+}
+
+impl<K: KnowledgeTrait> Clone for ValueDiff<K> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            added: self.added.clone(),
+            removed: self.removed.clone(),
+            old_strength: self.old_strength,
+            new_strength: self.new_strength,
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for ValueDiff<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("ValueDiff")
+            .field("value", &self.value)
+            .field("added", &self.added)
+            .field("removed", &self.removed)
+            .field("old_strength", &self.old_strength)
+            .field("new_strength", &self.new_strength)
+            .finish()
+    }
+}
+
+/// What changed about a single facet.
+pub struct FacetDiff<K: KnowledgeTrait> {
+    /// The facet.
+    pub facet: K::Facet,
+    /// The strongest belief before, or the truth for a reflexive model.
+    pub old_strongest: Option<K::FacetValue>,
+    /// The strongest belief after, or the truth for a reflexive model.
+    pub new_strongest: Option<K::FacetValue>,
+    /// Every value whose evidence or total strength changed.
+    pub values: Vec<ValueDiff<K>>,
+    /// Every piece of evidence that mutated.
+    pub mutations: Vec<EvidenceMutation<K>>,
+}
+
+impl<K: KnowledgeTrait> FacetDiff<K> {
+    /// Check whether the strongest belief changed.
+    pub fn strongest_changed(&self) -> bool {
+        self.old_strongest != self.new_strongest
+    }
+}
+
+impl<K: KnowledgeTrait> Clone for FacetDiff<K> {
+    fn clone(&self) -> Self {
+        Self {
+            facet: self.facet.clone(),
+            old_strongest: self.old_strongest.clone(),
+            new_strongest: self.new_strongest.clone(),
+            values: self.values.clone(),
+            mutations: self.mutations.clone(),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for FacetDiff<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("FacetDiff")
+            .field("facet", &self.facet)
+            .field("old_strongest", &self.old_strongest)
+            .field("new_strongest", &self.new_strongest)
+            .field("values", &self.values)
+            .field("mutations", &self.mutations)
+            .finish()
+    }
+}
+
+/// What changed in a mental model between two [snapshots](ModelSnapshot).
+///
+/// Evidence is matched between the snapshots by its kind and data, so evidence whose strength
+/// merely decayed isn't reported as added or removed. The [`Display`]
+/// implementation writes a human-readable report.
+///
+/// [`Display`]: std::fmt::Display
+pub struct ModelDiff<K: KnowledgeTrait> {
+    /// The holder of the model.
+    pub holder: K::Entity,
+    /// The entity the model regards.
+    pub regarding: K::Entity,
+    /// The time of the earlier snapshot.
+    pub from: f32,
+    /// The time of the later snapshot.
+    pub to: f32,
+    /// Every facet that changed.
+    pub facets: Vec<FacetDiff<K>>,
+}
+
+/// The strongest belief (or truth) and facet data of every facet of a model.
+type FacetView<'a, K> = KnowledgeMap<
+    &'a <K as KnowledgeTrait>::Facet,
+    (Option<&'a <K as KnowledgeTrait>::FacetValue>, Option<&'a FacetData<K>>),
+>;
+
+fn view<K: KnowledgeTrait>(model: &MentalModel<K>) -> FacetView<'_, K> {
+    match model {
+        MentalModel::Reflexive(reflexive) => reflexive.facets
+            .iter()
+            .map(|(facet, truth)| (facet, (Some(truth), None)))
+            .collect(),
+        MentalModel::Evidence(evidence) => evidence.facets
+            .iter()
+            .map(|(facet, data)| (facet, (data.strongest(), Some(data))))
+            .collect(),
+    }
+}
+
+/// Check whether two pieces of evidence are the same between snapshots, ignoring their strength.
+fn same<K: KnowledgeTrait>(left: &Evidence<K>, right: &Evidence<K>) -> bool
+where
+    K::Data: PartialEq,
+    K::CustomKind: PartialEq,
+{
+    left.kind == right.kind && left.data == right.data
+}
+
+/// Match up the evidence of a value in two snapshots, returning the unmatched old and new
+/// evidence.
+fn match_evidence<'a, K: KnowledgeTrait>(
+    old: &'a [Evidence<K>],
+    new: &'a [Evidence<K>],
+) -> (Vec<&'a Evidence<K>>, Vec<&'a Evidence<K>>)
+where
+    K::Data: PartialEq,
+    K::CustomKind: PartialEq,
+{
+    let mut unmatched: Vec<_> = old.iter().map(Some).collect();
+    let mut added = Vec::new();
+    for evidence in new {
+        let matched = unmatched
+            .iter_mut()
+            .find(|old| old.is_some_and(|old| same(old, evidence)));
+        if let Some(matched) = matched {
+            *matched = None;
+        } else {
+            added.push(evidence);
+        }
+    }
+    (unmatched.into_iter().flatten().collect(), added)
+}
+
+impl<K: KnowledgeTrait> ModelDiff<K>
+where
+    K::Data: PartialEq,
+    K::CustomKind: PartialEq,
+{
+    /// Compute what changed between two versions of a mental model, taken at the times `from` and
+    /// `to`.
+    pub fn between(old: &MentalModel<K>, new: &MentalModel<K>, from: f32, to: f32) -> Self {
+        let (holder, regarding) = match new {
+            MentalModel::Reflexive(reflexive) => (&reflexive.holder, &reflexive.holder),
+            MentalModel::Evidence(evidence) => (&evidence.holder, &evidence.regarding),
+        };
+        let old_view = view(old);
+        let new_view = view(new);
+        let mut facets: Vec<_> = new_view.keys().copied().collect();
+        facets.extend(old_view.keys().filter(|facet| !new_view.contains_key(*facet)));
+
+        ModelDiff {
+            holder: holder.clone(),
+            regarding: regarding.clone(),
+            from,
+            to,
+            facets: facets
+                .into_iter()
+                .filter_map(|facet| {
+                    let old = old_view.get(facet).copied().unwrap_or_default();
+                    let new = new_view.get(facet).copied().unwrap_or_default();
+                    let diff = Self::diff_facet(facet, old, new);
+                    let changed = diff.strongest_changed()
+                        || !diff.values.is_empty()
+                        || !diff.mutations.is_empty();
+                    changed.then_some(diff)
+                })
+                .collect(),
+        }
+    }
+
+    fn diff_facet(
+        facet: &K::Facet,
+        (old_strongest, old): (Option<&K::FacetValue>, Option<&FacetData<K>>),
+        (new_strongest, new): (Option<&K::FacetValue>, Option<&FacetData<K>>),
+    ) -> FacetDiff<K> {
+        let old_values = old.map(FacetData::values);
+        let new_values = new.map(FacetData::values);
+        let mut values: Vec<_> = new_values.into_iter().flat_map(|v| v.keys()).collect();
+        values.extend(
+            old_values
+                .into_iter()
+                .flat_map(|v| v.keys())
+                .filter(|value| !new_values.is_some_and(|new| new.contains_key(*value))),
+        );
+
+        let mut unmatched: Vec<_> = values
+            .iter()
+            .map(|value| {
+                let old = old_values.and_then(|values| values.get(*value));
+                let new = new_values.and_then(|values| values.get(*value));
+                let (removed, added) = match_evidence(
+                    old.map_or(&[][..], FacetValueData::evidence),
+                    new.map_or(&[][..], FacetValueData::evidence),
+                );
+                let old_strength = old.map_or(0.0, FacetValueData::total_strength);
+                let new_strength = new.map_or(0.0, FacetValueData::total_strength);
+                (*value, removed, added, old_strength, new_strength)
+            })
+            .collect();
+
+        // find new mutations, whose earlier selves were removed from some value
+        let mut mutations = Vec::new();
+        for i in 0..unmatched.len() {
+            let mut j = 0;
+            while j < unmatched[i].2.len() {
+                let evidence = unmatched[i].2[j];
+                let earlier = |removed: &Evidence<K>| {
+                    evidence.earlier_versions().any(|previous| same(previous, removed))
+                };
+                let found = unmatched.iter().enumerate().find_map(|(from, (_, removed, ..))| {
+                    removed.iter().position(|removed| earlier(removed)).map(|k| (from, k))
+                });
+                if let Some((from, k)) = found {
+                    unmatched[from].1.remove(k);
+                    unmatched[i].2.remove(j);
+                    mutations.push(EvidenceMutation {
+                        from: unmatched[from].0.clone(),
+                        to: unmatched[i].0.clone(),
+                        evidence: evidence.clone(),
+                    });
+                } else {
+                    j += 1;
+                }
+            }
+        }
+
+        FacetDiff {
+            facet: facet.clone(),
+            old_strongest: old_strongest.cloned(),
+            new_strongest: new_strongest.cloned(),
+            values: unmatched
+                .into_iter()
+                .filter(|(_, removed, added, old_strength, new_strength)| {
+                    !removed.is_empty() || !added.is_empty() || old_strength != new_strength
+                })
+                .map(|(value, removed, added, old_strength, new_strength)| ValueDiff {
+                    value: value.clone(),
+                    added: added.into_iter().cloned().collect(),
+                    removed: removed.into_iter().cloned().collect(),
+                    old_strength,
+                    new_strength,
+                })
+                .collect(),
+            mutations,
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> ModelDiff<K> {
+    /// Check whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.facets.is_empty()
+    }
+}
+
+impl<K: KnowledgeTrait> Evidence<K> {
+    /// Iterate over the earlier versions of this evidence from before it mutated, latest first.
+    fn earlier_versions(&self) -> impl Iterator<Item = &Evidence<K>> {
+        std::iter::successors(Some(self), |evidence| match &evidence.kind {
+            EvidenceKind::Mutation { previous } => Some(previous),
+            _ => None,
+        })
+        .skip(1)
+    }
+}
+
+impl<K: KnowledgeTrait> Clone for ModelDiff<K> {
+    fn clone(&self) -> Self {
+        Self {
+            holder: self.holder.clone(),
+            regarding: self.regarding.clone(),
+            from: self.from,
+            to: self.to,
+            facets: self.facets.clone(),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for ModelDiff<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("ModelDiff")
+            .field("holder", &self.holder)
+            .field("regarding", &self.regarding)
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .field("facets", &self.facets)
+            .finish()
+    }
+}
+
+impl<K: KnowledgeTrait> std::fmt::Display for ModelDiff<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{:?} of {:?}, from {} to {}:", self.holder, self.regarding, self.from, self.to)?;
+        if self.is_empty() {
+            return write!(f, " no changes");
+        }
+
+        for facet in self.facets.iter() {
+            write!(f, "\n  {:?}:", facet.facet)?;
+            if facet.strongest_changed() {
+                write!(f, " {:?} -> {:?}", facet.old_strongest, facet.new_strongest)?;
+            }
+            for value in facet.values.iter() {
+                write!(
+                    f,
+                    "\n    {:?}: strength {} -> {}",
+                    value.value,
+                    value.old_strength,
+                    value.new_strength,
+                )?;
+                for evidence in value.added.iter() {
+                    write!(f, "\n      + {:?} ({})", evidence.kind.tag(), evidence.strength)?;
+                }
+                for evidence in value.removed.iter() {
+                    write!(f, "\n      - {:?} ({})", evidence.kind.tag(), evidence.strength)?;
+                }
+            }
+            for mutation in facet.mutations.iter() {
+                let kind = mutation.evidence.principal_kind().tag();
+                write!(f, "\n    {:?} -> {:?}: mutated {kind:?}", mutation.from, mutation.to)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn diffs_report_evidence_and_beliefs() {
+        let mut model = EvidenceModel::new(alice(), bob());
+        let observed = evidence(EvidenceKind::Observation { location: carol().id() }, 1.5);
+        let implanted = evidence(EvidenceKind::Implantation, 1.0);
+        model.insert_evidence(TestValue::Red, observed.clone());
+        model.insert_evidence(TestValue::Green, implanted.clone());
+        model.insert_evidence(TestValue::Farmer, implanted.clone());
+        let before = model.snapshot(0.0);
+        assert!(before.diff(&model.snapshot(1.0)).is_empty());
+
+        model.update_facet(TestFacet::HairColor, |data| {
+            data.update_value(TestValue::Red, |data| {
+                data.update_evidence(|evidence| evidence.clear());
+            });
+            data.insert_evidence(TestValue::Blue, observed.mutated());
+            data.insert_evidence(TestValue::Green, implanted);
+        });
+        let diff = before.diff(&model.snapshot(2.0));
+        assert_eq!(diff.facets.len(), 1);
+        let hair = &diff.facets[0];
+        assert_eq!(hair.facet, TestFacet::HairColor);
+        assert_eq!(hair.old_strongest, Some(TestValue::Red));
+        assert_eq!(hair.new_strongest, Some(TestValue::Green));
+        assert_eq!(hair.mutations.len(), 1);
+        assert_eq!(hair.mutations[0].from, TestValue::Red);
+        assert_eq!(hair.mutations[0].to, TestValue::Blue);
+        let red = hair.values.iter().find(|diff| diff.value == TestValue::Red).unwrap();
+        assert!(red.removed.is_empty() && red.added.is_empty());
+        assert_eq!(red.strength_delta(), -1.5);
+        let green = hair.values.iter().find(|diff| diff.value == TestValue::Green).unwrap();
+        assert_eq!(green.added.len(), 1);
+
+        let report = diff.to_string();
+        assert!(report.contains("HairColor: Some(Red) -> Some(Green)"));
+        assert!(report.contains("Red -> Blue: mutated Observation"));
+    }
+}
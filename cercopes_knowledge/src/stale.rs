@@ -0,0 +1,380 @@
+use super::*;
+
+/// A holder's strongest belief of a facet of an entity that doesn't match the truth.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K::Facet: Serialize, K::FacetValue: Serialize, K::Entity: Serialize",
+    deserialize = "
+        K::Facet: for<'a> Deserialize<'a>,
+        K::FacetValue: for<'a> Deserialize<'a>,
+        K::Entity: for<'a> Deserialize<'a>,
+    ",
+))]
+pub struct StaleBelief<K: KnowledgeTrait> {
+    /// The entity with the stale belief.
+    pub holder: K::Entity,
+    /// The entity the belief is about.
+    pub regarding: K::Entity,
+    /// The facet the belief is about.
+    pub facet: K::Facet,
+    /// The strongest belief of the facet.
+    pub believed: K::FacetValue,
+    /// The true value of the facet.
+    pub truth: K::FacetValue,
+    /// When the belief was first found to be wrong.
+    pub since: f32,
+}
+
+impl<K: KnowledgeTrait> StaleBelief<K> {
+    /// Get how long the belief has been wrong at the time `now`.
+    pub fn wrong_for(&self, now: f32) -> f32 {
+        now - self.since
+    }
+}
+
+impl<K: KnowledgeTrait> Clone for StaleBelief<K> {
+    fn clone(&self) -> Self {
+        Self {
+            holder: self.holder.clone(),
+            regarding: self.regarding.clone(),
+            facet: self.facet.clone(),
+            believed: self.believed.clone(),
+            truth: self.truth.clone(),
+            since: self.since,
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for StaleBelief<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("StaleBelief")
+            .field("holder", &self.holder)
+            .field("regarding", &self.regarding)
+            .field("facet", &self.facet)
+            .field("believed", &self.believed)
+            .field("truth", &self.truth)
+            .field("since", &self.since)
+            .finish()
+    }
+}
+
+/// What a model remembers about a stale belief, without the holder, regarded entity and facet
+/// that the [stale belief](StaleBelief) is built from.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K::FacetValue: Serialize",
+    deserialize = "K::FacetValue: for<'a> Deserialize<'a>",
+))]
+pub(crate) struct Staleness<K: KnowledgeTrait> {
+    believed: K::FacetValue,
+    truth: K::FacetValue,
+    since: f32,
+}
+
+impl<K: KnowledgeTrait> Clone for Staleness<K> {
+    fn clone(&self) -> Self {
+        Self {
+            believed: self.believed.clone(),
+            truth: self.truth.clone(),
+            since: self.since,
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for Staleness<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("Staleness")
+            .field("believed", &self.believed)
+            .field("truth", &self.truth)
+            .field("since", &self.since)
+            .finish()
+    }
+}
+
+/// A strongest belief becoming wrong or right again, found by
+/// [tracking staleness](EvidenceModel::track_staleness).
+pub enum StalenessChange<K: KnowledgeTrait> {
+    /// A belief became wrong, such as when the regarded entity dyed their hair.
+    Stale(StaleBelief<K>),
+    /// A stale belief was corrected, such as when the holder saw the dyed hair, or the truth
+    /// changed back.
+    Corrected {
+        /// The belief as it was when it was last found to be wrong.
+        belief: StaleBelief<K>,
+        /// When the belief was found to be right again.
+        at: f32,
+    },
+    /// A stale belief was let go of without being corrected, such as when all of its evidence was
+    /// forgotten, or the facet was removed from the model.
+    Forgotten {
+        /// The belief as it was when it was last found to be wrong.
+        belief: StaleBelief<K>,
+        /// When the belief was found to be gone.
+        at: f32,
+    },
+}
+
+impl<K: KnowledgeTrait> StalenessChange<K> {
+    /// Get the stale belief that became wrong or was corrected.
+    pub fn belief(&self) -> &StaleBelief<K> {
+        match self {
+            StalenessChange::Stale(belief)
+            | StalenessChange::Corrected { belief, .. }
+            | StalenessChange::Forgotten { belief, .. } => belief,
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Clone for StalenessChange<K> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Stale(belief) => Self::Stale(belief.clone()),
+            Self::Corrected { belief, at } => Self::Corrected {
+                belief: belief.clone(),
+                at: *at,
+            },
+            Self::Forgotten { belief, at } => Self::Forgotten {
+                belief: belief.clone(),
+                at: *at,
+            },
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for StalenessChange<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::Stale(belief) => f
+                .debug_tuple("Stale")
+                .field(belief)
+                .finish(),
+            Self::Corrected { belief, at } => f
+                .debug_struct("Corrected")
+                .field("belief", belief)
+                .field("at", at)
+                .finish(),
+            Self::Forgotten { belief, at } => f
+                .debug_struct("Forgotten")
+                .field("belief", belief)
+                .field("at", at)
+                .finish(),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> FacetData<K> {
+    /// Check whether the strongest belief is the truth.
+    ///
+    /// Returns `None` if there is no strongest belief.
+    pub fn matches_truth(&self) -> Option<bool> {
+        self.strongest().map(|strongest| *strongest == self.truth)
+    }
+}
+
+impl<K: KnowledgeTrait> EvidenceModel<K> {
+    /// Check whether the strongest belief of a facet is the truth.
+    ///
+    /// Returns `None` if there is no strongest belief. See [`FacetData::matches_truth`].
+    pub fn matches_truth(&self, facet: &K::Facet) -> Option<bool> {
+        self.facets.get(facet)?.matches_truth()
+    }
+
+    /// Build the stale belief of a facet from what this model remembers about it.
+    fn stale_belief(&self, facet: &K::Facet, staleness: &Staleness<K>) -> StaleBelief<K> {
+        StaleBelief {
+            holder: self.holder.clone(),
+            regarding: self.regarding.clone(),
+            facet: facet.clone(),
+            believed: staleness.believed.clone(),
+            truth: staleness.truth.clone(),
+            since: staleness.since,
+        }
+    }
+
+    /// Get the stale belief of a facet, as of when staleness was last
+    /// [tracked](EvidenceModel::track_staleness).
+    pub fn get_stale(&self, facet: &K::Facet) -> Option<StaleBelief<K>> {
+        self.stale.get(facet).map(|staleness| self.stale_belief(facet, staleness))
+    }
+
+    /// Get every stale belief, as of when staleness was last
+    /// [tracked](EvidenceModel::track_staleness).
+    pub fn stale_beliefs(&self) -> impl Iterator<Item = StaleBelief<K>> + '_ {
+        self.stale.iter().map(|(facet, staleness)| self.stale_belief(facet, staleness))
+    }
+
+    /// Compare every strongest belief to the truth at the time `now`, returning every belief that
+    /// became wrong, was corrected, or was forgotten since staleness was last tracked.
+    ///
+    /// A belief is wrong from the first time it's tracked as wrong, so how long it has been wrong
+    /// is only as precise as how often this is called. A stale belief that changes to another
+    /// wrong value stays stale since the same time. Facets without a strongest belief are never
+    /// stale, so a stale belief whose facet loses its strongest belief (or is removed from the
+    /// model) is [forgotten](StalenessChange::Forgotten) rather than corrected.
+    pub fn track_staleness(&mut self, now: f32) -> Vec<StalenessChange<K>> {
+        let mut changes = Vec::new();
+        let mut stale = std::mem::take(&mut self.stale);
+
+        for (facet, data) in self.facets.iter() {
+            let strongest = data.strongest();
+            let believed = strongest.filter(|strongest| **strongest != data.truth);
+            match (believed, stale.remove(facet)) {
+                (Some(believed), Some(mut staleness)) => {
+                    staleness.believed.clone_from(believed);
+                    staleness.truth.clone_from(&data.truth);
+                    self.stale.insert(facet.clone(), staleness);
+                },
+                (Some(believed), None) => {
+                    let staleness = Staleness {
+                        believed: believed.clone(),
+                        truth: data.truth.clone(),
+                        since: now,
+                    };
+                    changes.push(StalenessChange::Stale(self.stale_belief(facet, &staleness)));
+                    self.stale.insert(facet.clone(), staleness);
+                },
+                (None, Some(staleness)) => {
+                    let belief = self.stale_belief(facet, &staleness);
+                    changes.push(if strongest.is_some() {
+                        StalenessChange::Corrected { belief, at: now }
+                    } else {
+                        StalenessChange::Forgotten { belief, at: now }
+                    });
+                },
+                (None, None) => (),
+            }
+        }
+
+        for (facet, staleness) in stale {
+            let belief = self.stale_belief(&facet, &staleness);
+            changes.push(StalenessChange::Forgotten { belief, at: now });
+        }
+
+        changes
+    }
+
+    /// Update all of the true facet values in this model, then [track
+    /// staleness](EvidenceModel::track_staleness) at the time `now`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a true value can't be found. See [`EvidenceModel::try_update_truths_at`].
+    pub fn update_truths_at(&mut self, now: f32) -> Vec<StalenessChange<K>> {
+        or_panic(self.try_update_truths_at(now))
+    }
+
+    /// Update all of the true facet values in this model, then [track
+    /// staleness](EvidenceModel::track_staleness) at the time `now`, or return an error if a true
+    /// value can't be found.
+    pub fn try_update_truths_at(
+        &mut self,
+        now: f32,
+    ) -> Result<Vec<StalenessChange<K>>, KnowledgeError> {
+        self.try_update_truths()?;
+        Ok(self.track_staleness(now))
+    }
+}
+
+impl<K: KnowledgeTrait> Mind<K>
+where
+    K::Entity: Eq + Hash,
+{
+    /// Get every stale belief of every model, as of when staleness was last
+    /// [tracked](Mind::track_staleness).
+    ///
+    /// Models are in no particular order.
+    pub fn stale_beliefs(&self) -> impl Iterator<Item = StaleBelief<K>> + '_ {
+        self.models.values().flat_map(EvidenceModel::stale_beliefs)
+    }
+
+    /// [Track the staleness](EvidenceModel::track_staleness) of every model at the time `now`.
+    ///
+    /// Relation models, nested models and the reflexive model aren't tracked. Models are in no
+    /// particular order.
+    pub fn track_staleness(&mut self, now: f32) -> Vec<StalenessChange<K>> {
+        self.models
+            .values_mut()
+            .flat_map(|model| model.track_staleness(now))
+            .collect()
+    }
+
+    /// Update all of the true facet values in every model, then [track
+    /// staleness](Mind::track_staleness) at the time `now`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a true value can't be found. See [`Mind::try_update_truths_at`].
+    pub fn update_truths_at(&mut self, now: f32) -> Vec<StalenessChange<K>> {
+        or_panic(self.try_update_truths_at(now))
+    }
+
+    /// Update all of the true facet values in every model, then [track
+    /// staleness](Mind::track_staleness) at the time `now`, or return an error if a true value
+    /// can't be found.
+    pub fn try_update_truths_at(
+        &mut self,
+        now: f32,
+    ) -> Result<Vec<StalenessChange<K>>, KnowledgeError> {
+        self.try_update_truths()?;
+        Ok(self.track_staleness(now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn dyed_hair_makes_beliefs_stale() {
+        let mut mind = Mind::<TestTypes>::new(alice());
+        let observed = || evidence(EvidenceKind::Observation { location: alice().id() }, 1.0);
+        mind.insert_evidence(&bob(), TestValue::Green, observed());
+        mind.insert_evidence(&bob(), TestValue::Smith, observed());
+        assert!(mind.update_truths_at(0.0).is_empty());
+        let hair = &TestFacet::HairColor;
+        assert_eq!(mind.get_model(&bob()).unwrap().matches_truth(hair), Some(true));
+
+        mind.get_model_mut(&bob()).regarding.hair = TestValue::Red;
+        let changes = mind.update_truths_at(1.0);
+        assert_eq!(changes.len(), 1);
+        assert!(matches!(&changes[0], StalenessChange::Stale(belief)
+            if belief.believed == TestValue::Green && belief.truth == TestValue::Red));
+        assert!(mind.track_staleness(3.0).is_empty());
+        let model = mind.get_model(&bob()).unwrap();
+        assert_eq!(model.matches_truth(hair), Some(false));
+        assert_eq!(model.get_stale(hair).unwrap().wrong_for(3.0), 2.0);
+        assert_eq!(mind.stale_beliefs().count(), 1);
+
+        mind.insert_evidence(&bob(), TestValue::Red, evidence(EvidenceKind::Observation {
+            location: alice().id(),
+        }, 2.0));
+        let changes = mind.track_staleness(4.0);
+        assert_eq!(changes.len(), 1);
+        assert!(matches!(&changes[0], StalenessChange::Corrected { belief, at: 4.0 }
+            if belief.wrong_for(4.0) == 3.0));
+        assert_eq!(mind.stale_beliefs().count(), 0);
+    }
+
+    #[test]
+    fn stale_beliefs_without_evidence_are_forgotten() {
+        let mut model = EvidenceModel::<TestTypes>::new(alice(), bob());
+        let observed = || evidence(EvidenceKind::Observation { location: alice().id() }, 1.0);
+        model.insert_evidence(TestValue::Red, observed());
+        model.insert_evidence(TestValue::Farmer, observed());
+        assert_eq!(model.track_staleness(0.0).len(), 2);
+        let stale = model.get_stale(&TestFacet::HairColor).unwrap();
+        assert_eq!((stale.holder, stale.regarding), (alice(), bob()));
+
+        model.forget(&ForgetPolicy::new().with_min_strength(2.0));
+        model.facets.remove(&TestFacet::Job);
+        let changes = model.track_staleness(1.0);
+        assert_eq!(changes.len(), 2);
+        assert!(changes.iter().all(|change| {
+            matches!(change, StalenessChange::Forgotten { belief, at: 1.0 } if belief.since == 0.0)
+        }));
+        assert_eq!(model.stale_beliefs().count(), 0);
+    }
+}
@@ -0,0 +1,291 @@
+use super::*;
+
+/// What a holder has learned about the honesty of a particular source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrustRecord {
+    /// The total strength of the source's statements that were confirmed by observation.
+    pub confirmed: f32,
+    /// The total strength of the source's statements that were contradicted by observation.
+    pub contradicted: f32,
+}
+
+/// What a facet contributed to the trust records, by source.
+type Revision<K> = KnowledgeMap<<K as KnowledgeTrait>::EntityId, TrustRecord>;
+
+impl TrustRecord {
+    /// Add another record to this one.
+    fn add(&mut self, other: &TrustRecord) {
+        self.confirmed += other.confirmed;
+        self.contradicted += other.contradicted;
+    }
+
+    /// Take another record away from this one.
+    fn sub(&mut self, other: &TrustRecord) {
+        self.confirmed -= other.confirmed;
+        self.contradicted -= other.contradicted;
+    }
+}
+
+/// A holder's trust in the entities that make [statements](EvidenceKind::Statement) to them.
+///
+/// Trust in a source is a number in `[0.0, 1.0]` that starts at `default_trust` and moves towards
+/// the ratio of the source's confirmed statements as they are checked against
+/// [observations](EvidenceKind::Observation).
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K::EntityId: Serialize, K::Facet: Serialize",
+    deserialize = "
+        K::EntityId: for<'a> Deserialize<'a>,
+        K::Facet: for<'a> Deserialize<'a>,
+    ",
+))]
+pub struct Trust<K: KnowledgeTrait> {
+    /// The trust held in a source that has never been confirmed nor contradicted.
+    pub default_trust: f32,
+    /// How much `default_trust` counts for compared to the strength of a source's record.
+    ///
+    /// Higher values make trust slower to change.
+    pub prior_weight: f32,
+    /// Everything learned about each source.
+    pub records: KnowledgeMap<K::EntityId, TrustRecord>,
+    /// What each facet of each regarded entity last contributed to `records`, by source.
+    ///
+    /// See [`Trust::revise_facet`].
+    #[serde(default)]
+    revisions: KnowledgeMap<K::EntityId, KnowledgeMap<K::Facet, Revision<K>>>,
+}
+
+impl<K: KnowledgeTrait> Trust<K> {
+    /// Create a new set of trust scores with no records.
+    pub fn new(default_trust: f32, prior_weight: f32) -> Self {
+        Trust {
+            default_trust,
+            prior_weight,
+            records: KnowledgeMap::default(),
+            revisions: KnowledgeMap::default(),
+        }
+    }
+
+    /// Get the holder's current trust in a source.
+    pub fn trust(&self, source: &K::EntityId) -> f32 {
+        let record = self.records.get(source).copied().unwrap_or_default();
+        let total = self.prior_weight + record.confirmed + record.contradicted;
+        if total > 0.0 {
+            (self.prior_weight * self.default_trust + record.confirmed) / total
+        } else {
+            self.default_trust
+        }
+    }
+
+    /// Get the weight of a piece of evidence: the trust in its [source](Evidence::source).
+    ///
+    /// Evidence without a source has a weight of `1.0`. This can be passed to
+    /// [`EvidenceModel::reweigh`].
+    pub fn weight(&self, evidence: &Evidence<K>) -> f32 {
+        evidence.source().map_or(1.0, |source| self.trust(source))
+    }
+
+    /// Get the effective strength of a piece of evidence, scaled by the trust in its
+    /// [source](Evidence::source).
+    ///
+    /// Evidence without a source is unaffected.
+    pub fn effective_strength(&self, evidence: &Evidence<K>) -> f32 {
+        evidence.strength * self.weight(evidence)
+    }
+
+    /// Check the statements for a facet of `regarding` against what the holder has observed.
+    ///
+    /// Every [source](Evidence::source) that stated the [observed value](FacetData::observed_value)
+    /// is credited and every source that stated something else is discredited, proportional to the
+    /// strength of their statements. Misremembered statements aren't held against the source.
+    ///
+    /// This replaces whatever the facet contributed when it was last revised, so it can be called
+    /// every tick. If the holder hasn't observed the facet, it contributes nothing.
+    pub fn revise_facet(&mut self, regarding: &K::Entity, data: &FacetData<K>) {
+        let mut revision: Revision<K> = KnowledgeMap::default();
+
+        if let Some(observed) = data.observed_value() {
+            for (value, value_data) in data.values.iter() {
+                for evidence in value_data.evidence.iter() {
+                    if matches!(evidence.kind, EvidenceKind::Mutation { .. }) {
+                        continue;
+                    }
+                    let source = if let Some(source) = evidence.source() {
+                        source
+                    } else {
+                        continue;
+                    };
+                    let record = revision.entry(source.clone()).or_default();
+                    if value == observed {
+                        record.confirmed += evidence.strength.abs();
+                    } else {
+                        record.contradicted += evidence.strength.abs();
+                    }
+                }
+            }
+        }
+
+        for (source, record) in revision.iter() {
+            self.records.entry(source.clone()).or_default().add(record);
+        }
+        let old = self.revisions
+            .entry(regarding.id())
+            .or_default()
+            .insert(data.truth.facet(), revision);
+        for (source, record) in old.into_iter().flatten() {
+            self.records.entry(source).or_default().sub(&record);
+        }
+    }
+
+    /// Check the statements for every facet of a model against what the holder has observed.
+    ///
+    /// See [`Trust::revise_facet`].
+    pub fn revise(&mut self, model: &EvidenceModel<K>) {
+        for data in model.facets.values() {
+            self.revise_facet(&model.regarding, data);
+        }
+    }
+
+    /// Forget all records and rebuild them from a holder's models.
+    pub fn recompute<'a>(&mut self, models: impl IntoIterator<Item = &'a EvidenceModel<K>>)
+    where
+        K: 'a,
+    {
+        self.records.clear();
+        self.revisions.clear();
+        for model in models {
+            self.revise(model);
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> FacetData<K> {
+    /// Get the value that the holder has most strongly [observed](EvidenceKind::Observation)
+    /// first-hand, if any.
+    pub fn observed_value(&self) -> Option<&K::FacetValue> {
+        let mut max: Option<(&K::FacetValue, f32)> = None;
+
+        for (value, data) in self.values.iter() {
+            let observed: f32 = data.evidence
+                .iter()
+                .filter(|evidence| matches!(evidence.kind, EvidenceKind::Observation { .. }))
+                .map(|evidence| evidence.strength)
+                .sum();
+            if observed > 0.0 && max.is_none_or(|(_, max)| max < observed) {
+                max = Some((value, observed));
+            }
+        }
+
+        max.map(|(value, _)| value)
+    }
+}
+
+impl<K: KnowledgeTrait> Default for Trust<K> {
+    /// Fully trust every source until they are contradicted.
+    fn default() -> Self {
+        Trust {
+            default_trust: 1.0,
+            prior_weight: 1.0,
+            records: KnowledgeMap::default(),
+            revisions: KnowledgeMap::default(),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Clone for Trust<K> {
+    fn clone(&self) -> Self {
+        Self {
+            default_trust: self.default_trust,
+            prior_weight: self.prior_weight,
+            records: self.records.clone(),
+            revisions: self.revisions.clone(),
+        }
+    }
+}
+
+impl<K: KnowledgeTrait> Debug for Trust<K> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f
+            .debug_struct("Trust")
+            .field("default_trust", &self.default_trust)
+            .field("prior_weight", &self.prior_weight)
+            .field("records", &self.records)
+            .field("revisions", &self.revisions)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn contradicted_source_loses_trust() {
+        let mut trust = Trust::<TestTypes>::new(0.5, 1.0);
+        let mut model = EvidenceModel::new(alice(), bob());
+        model.insert_evidence(TestValue::Red, statement(carol(), 1.0));
+        model.insert_evidence(TestValue::Green, statement(bob(), 1.0));
+        trust.revise(&model);
+        assert_eq!(trust.trust(&carol().id()), 0.5);
+
+        model.insert_evidence(TestValue::Green, evidence(EvidenceKind::Observation {
+            location: bob().id(),
+        }, 1.0));
+        trust.revise(&model);
+        assert_eq!(trust.trust(&carol().id()), 0.25);
+        assert_eq!(trust.trust(&bob().id()), 0.75);
+        trust.revise(&model);
+        assert_eq!(trust.trust(&carol().id()), 0.25);
+        trust.recompute([&model]);
+        assert_eq!(trust.trust(&carol().id()), 0.25);
+
+        let letter = EvidenceKind::Custom(TestKind::Letter { writer: carol().id() });
+        model.insert_evidence(TestValue::Blue, evidence(letter, 2.0));
+        trust.revise(&model);
+        assert_eq!(trust.trust(&carol().id()), 0.125);
+    }
+
+    #[test]
+    fn misremembered_statements_are_forgiven() {
+        let mut trust = Trust::<TestTypes>::new(0.5, 1.0);
+        let mut model = EvidenceModel::new(alice(), bob());
+        model.insert_evidence(TestValue::Red, statement(carol(), 1.0).mutated());
+        model.insert_evidence(TestValue::Green, evidence(EvidenceKind::Observation {
+            location: bob().id(),
+        }, 1.0));
+        trust.revise(&model);
+        assert_eq!(trust.trust(&carol().id()), 0.5);
+    }
+
+    #[test]
+    fn trust_scales_strength() {
+        let mut trust = Trust::<TestTypes>::new(1.0, 1.0);
+        trust.records.insert(carol().id(), TrustRecord {
+            confirmed: 0.0,
+            contradicted: 9.0,
+        });
+        let mut model = EvidenceModel::new(alice(), bob());
+        model.insert_evidence(TestValue::Red, statement(carol(), 3.0));
+        model.insert_evidence(TestValue::Red, statement(carol(), 1.0));
+        model.insert_evidence(TestValue::Green, statement(bob(), 1.0));
+        model.set_observing(true);
+        model.reweigh(|evidence| trust.weight(evidence));
+        let hair = &model.facets[&TestFacet::HairColor];
+        assert!((hair.values[&TestValue::Red].total_strength - 0.4).abs() < 1e-6);
+        assert_eq!(hair.strongest, Some(TestValue::Green));
+        let changes = model.drain_changes();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].old, Some(TestValue::Red));
+        assert_eq!(changes[0].new, Some(TestValue::Green));
+
+        // the weights outlast decay and removal
+        model.decay(&ExponentialDecay { half_life: f32::INFINITY }, 1.0, 1.0);
+        let red = || model.get_value(&TestValue::Red).unwrap().total_strength();
+        assert!((red() - 0.4).abs() < 1e-6);
+        model.update_facet(TestFacet::HairColor, |data| {
+            data.update_value(TestValue::Red, |data| data.remove_evidence(0));
+        });
+        assert!((model.get_value(&TestValue::Red).unwrap().total_strength() - 0.1).abs() < 1e-6);
+    }
+}
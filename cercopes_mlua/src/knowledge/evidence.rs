@@ -4,15 +4,22 @@ use super::*;
 pub(crate) struct LuaEvidenceKind(pub LuaEvidence);
 
 impl LuaEvidenceKind {
-    pub fn borrow_mut(&self) -> Option<RefMut<EvidenceKind<KnowledgeTypes>>> {
-        Some(RefMut::map(self.0.borrow_mut()?, |evidence| &mut evidence.kind))
+    pub fn borrow(&self) -> LuaResult<Option<Ref<'_, EvidenceKind<KnowledgeTypes>>>> {
+        Ok(self.0.borrow()?.map(|evidence| Ref::map(evidence, |evidence| &evidence.kind)))
+    }
+
+    pub fn update<F, R>(&self, update: F) -> LuaResult<Option<R>>
+    where
+        F: FnOnce(&mut EvidenceKind<KnowledgeTypes>) -> R,
+    {
+        self.0.update(|evidence| update(&mut evidence.kind))
     }
 }
 
 macro_rules! lua_ek_field_is_ {
     ($fields:expr, $name:literal, $kind:pat) => {
         $fields.add_field_method_get(concat!("is_", $name), |_, evidence_kind| {
-            Ok(matches!(evidence_kind.borrow_mut().as_deref(), Some($kind)))
+            Ok(matches!(evidence_kind.borrow()?.as_deref(), Some($kind)))
         });
     };
 }
@@ -21,7 +28,7 @@ macro_rules! lua_ek_field_get_set {
     ($fields:expr, $name:literal, $kind:pat, $result:tt) => {
         #[allow(unused_parens)] // Necessary because rust-analyzer freaks out on a | in the pattern
         $fields.add_field_method_get($name, |_, evidence_kind| {
-            if let Some($kind) = evidence_kind.borrow_mut().as_deref() {
+            if let Some($kind) = evidence_kind.borrow()?.as_deref() {
                 Ok(Some($result.clone()))
             } else {
                 Ok(None)
@@ -29,9 +36,11 @@ macro_rules! lua_ek_field_get_set {
         });
         #[allow(unused_parens)] // Necessary because rust-analyzer freaks out on a | in the pattern
         $fields.add_field_method_set($name, |_, evidence_kind, new_val| {
-            if let Some($kind) = evidence_kind.borrow_mut().as_deref_mut() {
-                *$result = new_val;
-            }
+            evidence_kind.update(|kind| {
+                if let $kind = kind {
+                    *$result = new_val;
+                }
+            })?;
 
             Ok(())
         });
@@ -52,12 +61,19 @@ impl UserData for LuaEvidenceKind {
         lua_ek_field_is_!(fields, "implantation", EvidenceKind::Implantation);
         lua_ek_field_is_!(fields, "declaration", EvidenceKind::Declaration {..});
         lua_ek_field_is_!(fields, "mutation", EvidenceKind::Mutation {..});
+        lua_ek_field_is_!(fields, "custom", EvidenceKind::Custom(_));
         lua_ek_field_get_set! {
             fields, "source",
             (EvidenceKind::Statement { source, .. }
-            | EvidenceKind::Overheard { source, .. }),
+            | EvidenceKind::Overheard { source, .. }
+            | EvidenceKind::Custom(CustomKind { source: Some(source), .. })),
             source
         }
+        lua_ek_field_get_set! {
+            fields, "name",
+            EvidenceKind::Custom(CustomKind { name, .. }),
+            name
+        }
         lua_ek_field_get_set! {
             fields, "location",
             (EvidenceKind::Declaration { location, .. }
@@ -83,7 +99,7 @@ impl UserData for LuaEvidenceKind {
 
     fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
         methods.add_method("into_table", |_, evidence_kind, ()| {
-            if let Some(evidence_kind) = evidence_kind.borrow_mut() {
+            if let Some(evidence_kind) = evidence_kind.borrow()? {
                 Ok(Some(EvidenceKindBuilder::from(evidence_kind.clone())))
             } else {
                 Ok(None)
@@ -105,6 +121,21 @@ impl LuaEvidence {
     }
 
     fn get_evidence(
+        mut evidence: &Evidence<KnowledgeTypes>,
+        mutations: usize,
+    ) -> Option<&Evidence<KnowledgeTypes>> {
+        for _ in 0..mutations {
+            if let EvidenceKind::Mutation { ref previous } = evidence.kind {
+                evidence = previous;
+            } else {
+                return None;
+            }
+        }
+
+        Some(evidence)
+    }
+
+    fn get_evidence_mut(
         mut evidence: &mut Evidence<KnowledgeTypes>,
         mutations: usize,
     ) -> Option<&mut Evidence<KnowledgeTypes>> {
@@ -119,12 +150,29 @@ impl LuaEvidence {
         Some(evidence)
     }
 
-    pub fn borrow_mut(&self) -> Option<RefMut<Evidence<KnowledgeTypes>>> {
-        let mut value_data = self.value_data.borrow_mut()?;
-        let mut evidence = value_data.evidence.get_mut(self.index)?;
-        evidence = Self::get_evidence(evidence, self.mutations)?;
-        let evidence_ptr = evidence as *mut Evidence<KnowledgeTypes>;
-        Some(RefMut::map(value_data, |_| unsafe { evidence_ptr.as_mut().unwrap_unchecked() }))
+    pub fn borrow(&self) -> LuaResult<Option<Ref<'_, Evidence<KnowledgeTypes>>>> {
+        let value_data = if let Some(value_data) = self.value_data.borrow()? {
+            value_data
+        } else {
+            return Ok(None);
+        };
+        Ok(Ref::filter_map(value_data, |value_data| {
+            Self::get_evidence(value_data.evidence().get(self.index)?, self.mutations)
+        }).ok())
+    }
+
+    pub fn update<F, R>(&self, update: F) -> LuaResult<Option<R>>
+    where
+        F: FnOnce(&mut Evidence<KnowledgeTypes>) -> R,
+    {
+        self.value_data
+            .update(|value_data| {
+                value_data.update_evidence(|all_evidence| {
+                    let evidence = all_evidence.get_mut(self.index)?;
+                    Self::get_evidence_mut(evidence, self.mutations).map(update)
+                })
+            })
+            .map(Option::flatten)
     }
 }
 
@@ -139,7 +187,7 @@ impl UserData for LuaEvidence {
                     mutations: evidence.mutations - 1,
                     ..evidence.clone()
                 };
-                if evidence.borrow_mut().is_some() {
+                if evidence.borrow()?.is_some() {
                     Ok(Some(evidence))
                 } else {
                     Ok(None)
@@ -149,30 +197,38 @@ impl UserData for LuaEvidence {
             }
         });
         fields.add_field_method_get("data", |_, evidence| {
-            if let Some(evidence) = evidence.borrow_mut() {
+            if let Some(evidence) = evidence.borrow()? {
                 Ok(Some(evidence.data.clone()))
             } else {
                 Ok(None)
             }
         });
         fields.add_field_method_set("data", |_, evidence, data| {
-            if let Some(mut evidence) = evidence.borrow_mut() {
-                evidence.data = data;
-            }
+            evidence.update(|evidence| evidence.data = data)?;
 
             Ok(())
         });
         fields.add_field_method_get("strength", |_, evidence| {
-            if let Some(evidence) = evidence.borrow_mut() {
+            if let Some(evidence) = evidence.borrow()? {
                 Ok(Some(evidence.strength))
             } else {
                 Ok(None)
             }
         });
         fields.add_field_method_set("strength", |_, evidence, strength| {
-            if let Some(mut evidence) = evidence.borrow_mut() {
-                evidence.strength = strength;
+            evidence.update(|evidence| evidence.strength = strength)?;
+
+            Ok(())
+        });
+        fields.add_field_method_get("weight", |_, evidence| {
+            if let Some(evidence) = evidence.borrow()? {
+                Ok(Some(evidence.weight))
+            } else {
+                Ok(None)
             }
+        });
+        fields.add_field_method_set("weight", |_, evidence, weight| {
+            evidence.update(|evidence| evidence.weight = weight)?;
 
             Ok(())
         });
@@ -180,9 +236,7 @@ impl UserData for LuaEvidence {
             Ok(LuaEvidenceKind(evidence.clone()))
         });
         fields.add_field_method_set("kind", |_, evidence, kind| {
-            if let Some(mut evidence) = evidence.borrow_mut() {
-                evidence.kind = EvidenceKindBuilder::into(kind);
-            }
+            evidence.update(|evidence| evidence.kind = EvidenceKindBuilder::into(kind))?;
 
             Ok(())
         });
@@ -191,7 +245,7 @@ impl UserData for LuaEvidence {
     fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
         methods.add_method("principal_evidence", |_, evidence, ()| {
             let mut mutations = evidence.mutations;
-            if let Some(evidence) = evidence.borrow_mut() {
+            if let Some(evidence) = evidence.borrow()? {
                 let mut evidence = &*evidence;
                 while let EvidenceKind::Mutation { ref previous } = evidence.kind {
                     evidence = previous;
@@ -206,14 +260,12 @@ impl UserData for LuaEvidence {
             }))
         });
         methods.add_method("mutate", |_, evidence, ()| {
-            if let Some(mut evidence) = evidence.borrow_mut() {
-                evidence.mutate();
-            }
+            evidence.update(Evidence::mutate)?;
 
             Ok(())
         });
         methods.add_method("into_table", |_, evidence, ()| {
-            if let Some(evidence) = evidence.borrow_mut() {
+            if let Some(evidence) = evidence.borrow()? {
                 Ok(Some(EvidenceBuilder::from(evidence.clone())))
             } else {
                 Ok(None)
@@ -11,13 +11,15 @@ impl LuaFacetData {
         lua.create_userdata(self)
     }
 
-    pub fn borrow_mut(&self) -> Option<RefMut<FacetData<KnowledgeTypes>>> {
-        let model = self.model.borrow_mut();
-        if model.regarding.is_facet_relevant(&self.facet) {
-            Some(RefMut::map(model, |model| model.get_facet_data(self.facet.clone())))
-        } else {
-            None
+    pub fn borrow_mut(&self) -> LuaResult<Option<RefMut<'_, FacetData<KnowledgeTypes>>>> {
+        let regarding = self.model.borrow().regarding.clone();
+        if !regarding.try_is_facet_relevant(&self.facet).map_err(LuaError::external)? {
+            return Ok(None);
         }
+        let mut model = self.model.borrow_mut();
+        // initialize the facet data first, so that getting it below can't fail
+        model.try_get_facet_data(self.facet.clone()).map_err(LuaError::external)?;
+        Ok(Some(RefMut::map(model, |model| model.get_facet_data(self.facet.clone()))))
     }
 }
 
@@ -30,15 +32,15 @@ impl UserData for LuaFacetData {
             Ok(facet_data.model.clone())
         });
         fields.add_field_method_get("truth", |_, facet_data| {
-            if let Some(facet_data) = facet_data.borrow_mut() {
+            if let Some(facet_data) = facet_data.borrow_mut()? {
                 Ok(Some(facet_data.truth.clone()))
             } else {
                 Ok(None)
             }
         });
         fields.add_field_method_get("strongest", |_, facet_data| {
-            if let Some(facet_data) = facet_data.borrow_mut() {
-                Ok(Some(facet_data.strongest.clone()))
+            if let Some(facet_data) = facet_data.borrow_mut()? {
+                Ok(Some(facet_data.strongest().cloned()))
             } else {
                 Ok(None)
             }
@@ -57,8 +59,8 @@ impl UserData for LuaFacetData {
             }
         });
         methods.add_meta_method("__len", |_, facet_data, ()| {
-            if let Some(facet_data) = facet_data.borrow_mut() {
-                Ok(Some(facet_data.values.len()))
+            if let Some(facet_data) = facet_data.borrow_mut()? {
+                Ok(Some(facet_data.values().len()))
             } else {
                 Ok(None)
             }
@@ -66,9 +68,9 @@ impl UserData for LuaFacetData {
         methods.add_function("iter_values", |lua, facet_data: AnyUserData| {
             let mut visited = AHashSet::with_capacity(20);
             let iter = lua.create_function_mut(move |lua, (facet_data, _): (_, FacetValue)| {
-                let facet_data_borrow_opt = LuaFacetData::borrow_mut(&facet_data);
+                let facet_data_borrow_opt = LuaFacetData::borrow_mut(&facet_data)?;
                 if let Some(ref facet_data_borrow) = facet_data_borrow_opt {
-                    for value in facet_data_borrow.values.keys() {
+                    for value in facet_data_borrow.values().keys() {
                         if !visited.contains(&value.hash_string) {
                             visited.insert(value.hash_string.clone());
                             let value = value.clone();
@@ -89,26 +91,55 @@ impl UserData for LuaFacetData {
             Ok((iter, facet_data, Nil))
         });
         methods.add_method("recompute_strongest", |_, facet_data, ()| {
-            if let Some(mut facet_data) = facet_data.borrow_mut() {
+            if let Some(mut facet_data) = facet_data.borrow_mut()? {
                 facet_data.recompute_strongest();
             }
 
             Ok(())
         });
         methods.add_method("recompute_total_strengths", |_, facet_data, ()| {
-            if let Some(mut facet_data) = facet_data.borrow_mut() {
+            if let Some(mut facet_data) = facet_data.borrow_mut()? {
                 facet_data.recompute_total_strengths();
             }
 
             Ok(())
         });
+        methods.add_method("probability", |_, facet_data, value: FacetValue| {
+            if let Some(facet_data) = facet_data.borrow_mut()? {
+                Ok(Some(facet_data.probability(&value)))
+            } else {
+                Ok(None)
+            }
+        });
+        methods.add_method("distribution", |lua, facet_data, ()| {
+            let facet_data = if let Some(facet_data) = facet_data.borrow_mut()? {
+                facet_data
+            } else {
+                return Ok(None);
+            };
+            let distribution = facet_data.distribution();
+            let table = lua.create_table_with_capacity(distribution.len() as i32, 0)?;
+            for (i, (value, probability)) in distribution.into_iter().enumerate() {
+                let entry = lua.create_table_with_capacity(0, 2)?;
+                entry.set("value", value.clone())?;
+                entry.set("probability", probability)?;
+                table.raw_set(i + 1, entry)?;
+            }
+            Ok(Some(table))
+        });
+        methods.add_method("confidence", |_, facet_data, ()| {
+            Ok(facet_data.borrow_mut()?.map(|facet_data| facet_data.confidence()))
+        });
+        methods.add_method("uncertainty", |_, facet_data, ()| {
+            Ok(facet_data.borrow_mut()?.map(|facet_data| facet_data.uncertainty()))
+        });
         methods.add_method("update_truth", |_, facet_data, ()| {
             // help Rust realize it should un-borrow the model
             let regarding = {
                 facet_data.model.borrow_mut().regarding.clone()
             };
-            if let Some(mut facet_data) = facet_data.borrow_mut() {
-                facet_data.update_truth(&regarding)
+            if let Some(mut facet_data) = facet_data.borrow_mut()? {
+                facet_data.try_update_truth(&regarding).map_err(LuaError::external)?;
             }
 
             Ok(())
@@ -1,6 +1,7 @@
 use cercopes_knowledge::{
     Evidence, EvidenceKind, EvidenceModel, Facet as FacetTrait, FacetValueData, FacetData,
-    FacetValue as FacetValueTrait, Entity as EntityTrait, KnowledgeTrait, ReflexiveModel,
+    FacetValue as FacetValueTrait, Entity as EntityTrait, KnowledgeError, KnowledgeTrait,
+    ReflexiveModel, CustomKind as CustomKindTrait,
 };
 use rand::prelude::*;
 pub(crate) use evidence::*;
@@ -14,6 +15,9 @@ mod value_data;
 mod facet_data;
 mod model;
 
+/// The RNG used for mutating models from Lua.
+type LuaRng = rand_xoshiro::Xoroshiro64Star;
+
 #[derive(Debug, Display, Clone, From, Into, PartialEq, Eq, Hash, Deref, DerefMut, AsRef, AsMut)]
 /// A Lua facet.
 pub struct Facet(pub String);
@@ -80,23 +84,60 @@ impl FacetValueTrait<KnowledgeTypes> for FacetValue {
         &self,
         model: &EvidenceModel<KnowledgeTypes>,
         evidence: &Evidence<KnowledgeTypes>,
-        _: &mut R,
+        rng: &mut R,
     ) -> Option<Self> {
-        let all_evidence = &model.facets[&self.facet()].values[self].evidence;
+        self.try_try_mutate(model, evidence, rng).unwrap()
+    }
+
+    fn try_try_mutate<R: Rng>(
+        &self,
+        model: &EvidenceModel<KnowledgeTypes>,
+        evidence: &Evidence<KnowledgeTypes>,
+        rng: &mut R,
+    ) -> Result<Option<Self>, KnowledgeError> {
+        let all_evidence = model.facets[&self.facet()].values()[self].evidence();
         for (i, old_evidence) in all_evidence.iter().enumerate() {
             let old_evidence_p = old_evidence as *const Evidence<KnowledgeTypes>;
             if old_evidence_p == evidence {
-                return self.try_mutate
-                    .get(self.lua.get())
-                    .unwrap()
-                    .call((self.clone(), model.holder.clone(), model.regarding.clone(), i + 1))
-                    .unwrap();
+                let lua = self.lua.get();
+                return lua
+                    .scope(|scope| {
+                        // the callback draws from the model's RNG, so seeded mutations repeat
+                        let random = scope.create_function_mut(|_, args| lua_random(rng, args))?;
+                        self.try_mutate.get(lua)?.call((
+                            self.clone(),
+                            model.holder.clone(),
+                            model.regarding.clone(),
+                            i + 1,
+                            random,
+                        ))
+                    })
+                    .map_err(KnowledgeError::callback);
             }
         }
         unreachable!()
     }
 }
 
+/// Draw a random number like Lua's `math.random`.
+///
+/// With no arguments this is a float in `[0, 1)`, with `m` it's an integer in `[1, m]`, and with
+/// `m` and `n` it's an integer in `[m, n]`.
+fn lua_random<R: Rng>(
+    rng: &mut R,
+    (m, n): (Option<LuaInteger>, Option<LuaInteger>),
+) -> LuaResult<LuaValue<'static>> {
+    let (low, high) = match (m, n) {
+        (None, _) => return Ok(LuaValue::Number(rng.gen())),
+        (Some(m), None) => (1, m),
+        (Some(m), Some(n)) => (m, n),
+    };
+    if low > high {
+        return Err(LuaError::RuntimeError("bad argument to 'random' (interval is empty)".into()));
+    }
+    Ok(LuaValue::Integer(rng.gen_range(low..=high)))
+}
+
 impl ToLua<'_> for FacetValue {
     fn to_lua(self, lua: &Lua) -> LuaResult<LuaValue> {
         self.value.to_lua(lua)
@@ -109,8 +150,9 @@ impl FromLua<'_> for FacetValue {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Entity {
+/// The data of a Lua entity.
+#[derive(Debug, PartialEq, Eq)]
+pub struct EntityData {
     pub(crate) lua: LuaStatePtr,
     pub(crate) data: RegistryTable,
     pub(crate) relevant_facets: RegistryFunction,
@@ -118,21 +160,33 @@ pub struct Entity {
     pub(crate) is_facet_relevant: RegistryFunction,
 }
 
+/// A Lua entity.
+///
+/// This is shared, so that cloning it into every piece of evidence only costs a pointer.
+#[derive(Debug, Clone, PartialEq, Eq, Deref)]
+pub struct Entity(Rc<EntityData>);
+
+impl Hash for Entity {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.data.hash(state)
+    }
+}
+
 impl Entity {
     pub fn new_lua<'lua>(lua: &'lua Lua, table: LuaTable<'lua>) -> LuaResult<Self> {
-        Ok(Entity {
+        Ok(Entity(Rc::new(EntityData {
             lua: lua.into(),
             relevant_facets: table.get("relevant_facets")?,
             facet_truth: table.get("facet_truth")?,
             is_facet_relevant: table.get("is_facet_relevant")?,
             data: RegistryTable::new(lua, table)?,
-        })
+        })))
     }
 }
 
 impl<'lua> ToLua<'lua> for Entity {
     fn to_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
-        self.data.to_lua(lua)
+        self.data.clone().to_lua(lua)
     }
 }
 
@@ -142,30 +196,56 @@ impl<'lua> FromLua<'lua> for Entity {
     }
 }
 
-impl EntityTrait<KnowledgeTypes> for Entity {
-    fn relevant_facets(&self) -> Vec<Facet> {
+impl Entity {
+    fn call_relevant_facets(&self) -> LuaResult<Vec<Facet>> {
         let table: LuaTable = self.relevant_facets
-            .get(self.lua.get())
-            .unwrap()
-            .call(self.clone())
-            .unwrap();
-        table.sequence_values().map(|value| value.unwrap()).collect()
+            .get(self.lua.get())?
+            .call(self.clone())?;
+        table.sequence_values().collect()
     }
 
-    fn facet_truth(&self, facet: &Facet) -> Option<FacetValue> {
+    fn call_facet_truth(&self, facet: &Facet) -> LuaResult<Option<FacetValue>> {
         self.facet_truth
-            .get(self.lua.get())
-            .unwrap()
+            .get(self.lua.get())?
             .call((self.clone(), facet.clone()))
-            .unwrap()
     }
 
-    fn is_facet_relevant(&self, facet: &Facet) -> bool {
+    fn call_is_facet_relevant(&self, facet: &Facet) -> LuaResult<bool> {
         self.is_facet_relevant
-            .get(self.lua.get())
-            .unwrap()
+            .get(self.lua.get())?
             .call((self.clone(), facet.clone()))
-            .unwrap()
+    }
+}
+
+impl EntityTrait<KnowledgeTypes> for Entity {
+    fn id(&self) -> Entity {
+        self.clone()
+    }
+
+    fn relevant_facets(&self) -> Vec<Facet> {
+        self.call_relevant_facets().unwrap()
+    }
+
+    fn facet_truth(&self, facet: &Facet) -> Option<FacetValue> {
+        self.call_facet_truth(facet).unwrap()
+    }
+
+    fn try_relevant_facets(&self) -> Result<Vec<Facet>, KnowledgeError> {
+        self.call_relevant_facets().map_err(KnowledgeError::callback)
+    }
+
+    fn try_facet_truth(&self, facet: &Facet) -> Result<FacetValue, KnowledgeError> {
+        self.call_facet_truth(facet)
+            .map_err(KnowledgeError::callback)?
+            .ok_or_else(|| KnowledgeError::missing_truth(self, facet))
+    }
+
+    fn is_facet_relevant(&self, facet: &Facet) -> bool {
+        self.call_is_facet_relevant(facet).unwrap()
+    }
+
+    fn try_is_facet_relevant(&self, facet: &Facet) -> Result<bool, KnowledgeError> {
+        self.call_is_facet_relevant(facet).map_err(KnowledgeError::callback)
     }
 }
 
@@ -184,6 +264,27 @@ impl FromLua<'_> for Data {
     }
 }
 
+/// A custom Lua evidence kind.
+///
+/// In Lua, this is an evidence kind table like `{ kind = "custom", name = "letter", tag = 1 }`,
+/// optionally with a `source` entity. The tag defaults to `0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomKind {
+    pub name: String,
+    pub tag: u32,
+    pub source: Option<Entity>,
+}
+
+impl CustomKindTrait<KnowledgeTypes> for CustomKind {
+    fn tag(&self) -> u32 {
+        self.tag
+    }
+
+    fn source(&self) -> Option<&Entity> {
+        self.source.as_ref()
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 pub struct KnowledgeTypes;
 
@@ -194,7 +295,11 @@ impl KnowledgeTrait for KnowledgeTypes {
 
     type Entity = Entity;
 
+    type EntityId = Entity;
+
     type Data = Data;
+
+    type CustomKind = CustomKind;
 }
 
 pub(crate) fn initialize_lua(lua: &Lua) -> LuaResult<()> {
@@ -202,6 +307,10 @@ pub(crate) fn initialize_lua(lua: &Lua) -> LuaResult<()> {
     globals.set("Entity", lua.create_function(Entity::new_lua)?)?;
     globals.set("ReflexiveModel", LuaReflexiveModel::lua_new(lua)?)?;
     globals.set("EvidenceModel", LuaEvidenceModel::lua_new(lua)?)?;
+    globals.set("seed_rng", lua.create_function(|lua, seed: u64| {
+        lua.set_app_data(LuaRng::seed_from_u64(seed));
+        Ok(())
+    })?)?;
     Ok(())
 }
 
@@ -272,4 +381,169 @@ mod tests {
         assert!(success);
         Ok(())
     }
+
+    #[test]
+    fn test_entity() -> LuaResult<()> {
+        let lua = Lua::new();
+        crate::initialize_lua(&lua)?;
+        let entity: Entity = lua.load(r#"
+        return {
+            name = "alice",
+            relevant_facets = function(self)
+                return { "hair color" }
+            end,
+            facet_truth = function(self, facet)
+                return nil
+            end,
+            is_facet_relevant = function(self, facet)
+                return facet == "hair color"
+            end,
+        }
+        "#).eval()?;
+        let clone = entity.clone();
+        assert!(Rc::ptr_eq(&entity.0, &clone.0));
+        assert_eq!(std::mem::size_of::<Entity>(), std::mem::size_of::<usize>());
+        assert_eq!(clone.relevant_facets(), vec![Facet("hair color".to_string())]);
+        assert!(clone.is_facet_relevant(&Facet("hair color".to_string())));
+        let table = LuaTable::from_lua(clone.to_lua(&lua)?, &lua)?;
+        assert_eq!(table.get::<_, String>("name")?, "alice");
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_kind() -> LuaResult<()> {
+        let lua = Lua::new();
+        crate::initialize_lua(&lua)?;
+        let kind: EvidenceKindBuilder = lua.load(r#"
+        return { kind = "custom", name = "letter", tag = 3 }
+        "#).eval()?;
+        let kind: EvidenceKind<KnowledgeTypes> = kind.into();
+        assert_eq!(kind.tag(), cercopes_knowledge::EvidenceKindTag::Custom(3));
+        let table = LuaTable::from_lua(EvidenceKindBuilder::from(kind).to_lua(&lua)?, &lua)?;
+        assert_eq!(table.get::<_, String>("kind")?, "custom");
+        assert_eq!(table.get::<_, String>("name")?, "letter");
+        assert_eq!(table.get::<_, Option<Entity>>("source")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_entity_errors() -> LuaResult<()> {
+        let lua = Lua::new();
+        crate::initialize_lua(&lua)?;
+        let (nameless, broken): (Entity, Entity) = lua.load(r#"
+        local function relevant_facets(self)
+            return { "hair color" }
+        end
+        local function is_facet_relevant(self, facet)
+            return facet == "hair color"
+        end
+        return {
+            relevant_facets = relevant_facets,
+            facet_truth = function(self, facet)
+                return nil
+            end,
+            is_facet_relevant = is_facet_relevant,
+        }, {
+            relevant_facets = relevant_facets,
+            facet_truth = function(self, facet)
+                error("no hair")
+            end,
+            is_facet_relevant = function(self, facet)
+                error("no relevance")
+            end,
+        }
+        "#).eval()?;
+        let hair = Facet("hair color".to_string());
+        assert!(matches!(
+            nameless.try_facet_truth(&hair),
+            Err(KnowledgeError::MissingTruth { .. }),
+        ));
+        assert!(matches!(
+            broken.try_facet_truth(&hair),
+            Err(KnowledgeError::Callback(err)) if err.contains("no hair"),
+        ));
+        assert_eq!(nameless.try_is_facet_relevant(&hair), Ok(true));
+        assert!(matches!(
+            broken.try_is_facet_relevant(&hair),
+            Err(KnowledgeError::Callback(err)) if err.contains("no relevance"),
+        ));
+        assert!(ReflexiveModel::<KnowledgeTypes>::try_new(nameless).is_err());
+        assert!(EvidenceModel::<KnowledgeTypes>::try_new(broken.clone(), broken).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_value_errors() -> LuaResult<()> {
+        let lua = Lua::new();
+        crate::initialize_lua(&lua)?;
+        let (entity, red, evidence): (Entity, FacetValue, EvidenceBuilder) = lua.load(r#"
+        local red = {
+            facet = "hair color",
+            hash_string = "red",
+            try_mutate = function(...)
+                error("forgot")
+            end,
+        }
+        return {
+            relevant_facets = function(self)
+                return { "hair color" }
+            end,
+            facet_truth = function(self, facet)
+                return red
+            end,
+            is_facet_relevant = function(self, facet)
+                return facet == "hair color"
+            end,
+        }, red, { data = {}, kind = "implantation", strength = 1 }
+        "#).eval()?;
+        let mut model = EvidenceModel::<KnowledgeTypes>::new(entity.clone(), entity);
+        model.insert_evidence(red.clone(), evidence.into());
+        assert!(matches!(
+            model.try_mutate(&mut LuaRng::seed_from_u64(0)),
+            Err(KnowledgeError::Callback(err)) if err.contains("forgot"),
+        ));
+        assert_eq!(model.get_value(&red).unwrap().evidence().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_data_errors() -> LuaResult<()> {
+        let lua = Lua::new();
+        crate::initialize_lua(&lua)?;
+        let (facet_data, value_data): (LuaFacetData, LuaFacetValueData) = lua.load(r#"
+        local entity = {
+            relevant_facets = function(self)
+                return {}
+            end,
+            facet_truth = function(self, facet)
+                error("no hair")
+            end,
+            is_facet_relevant = function(self, facet)
+                return facet == "hair color"
+            end,
+        }
+        local red = {
+            facet = "hair color",
+            hash_string = "red",
+            try_mutate = function(...)
+                return nil
+            end,
+        }
+        local facet_data = EvidenceModel(entity, entity)["hair color"]
+        return facet_data, facet_data[red]
+        "#).eval()?;
+        assert!(matches!(
+            facet_data.borrow_mut(),
+            Err(err) if err.to_string().contains("no hair"),
+        ));
+        assert!(matches!(
+            value_data.borrow(),
+            Err(err) if err.to_string().contains("no hair"),
+        ));
+        assert!(matches!(
+            value_data.update(|_| ()),
+            Err(err) if err.to_string().contains("no hair"),
+        ));
+        Ok(())
+    }
 }
@@ -14,7 +14,8 @@ impl LuaReflexiveModel {
 
     pub(super) fn lua_new<'lua>(lua: &'lua Lua) -> LuaResult<Function<'lua>> {
         lua.create_function(|lua, holder| {
-            LuaReflexiveModel::new(lua, ReflexiveModel::new(holder))
+            let model = ReflexiveModel::try_new(holder).map_err(LuaError::external)?;
+            LuaReflexiveModel::new(lua, model)
         })
     }
 }
@@ -39,8 +40,7 @@ impl UserData for LuaReflexiveModel {
             Ok(())
         });
         methods.add_method("update_truths", |_, model, ()| {
-            model.borrow_mut().update_truths();
-            Ok(())
+            model.borrow_mut().try_update_truths().map_err(LuaError::external)
         });
     }
 }
@@ -58,7 +58,8 @@ impl LuaEvidenceModel {
 
     pub(super) fn lua_new<'lua>(lua: &'lua Lua) -> LuaResult<Function<'lua>> {
         lua.create_function(|lua, (holder, regarding)| {
-            LuaEvidenceModel::new(lua, EvidenceModel::new(holder, regarding))
+            let model = EvidenceModel::try_new(holder, regarding).map_err(LuaError::external)?;
+            LuaEvidenceModel::new(lua, model)
         })
     }
 }
@@ -80,7 +81,8 @@ impl UserData for LuaEvidenceModel {
             Ok(left.holder == right.holder && left.regarding == right.regarding)
         });
         methods.add_meta_method("__index", |lua, model, facet| {
-            if model.borrow().regarding.is_facet_relevant(&facet) {
+            let regarding = model.borrow().regarding.clone();
+            if regarding.try_is_facet_relevant(&facet).map_err(LuaError::external)? {
                 LuaFacetData {
                     model: model.clone(),
                     facet,
@@ -120,15 +122,26 @@ impl UserData for LuaEvidenceModel {
             Ok(())
         });
         methods.add_method("update_truth", |_, model, ()| {
-            model.borrow_mut().update_truths();
-            Ok(())
+            model.borrow_mut().try_update_truths().map_err(LuaError::external)
         });
         methods.add_method("get_strongest_belief", |_, model, facet| {
             Ok(model.borrow().get_strongest_belief(&facet).cloned())
         });
-        methods.add_method("mutate", |_, model, ()| {
-            model.borrow_mut().mutate(&mut thread_rng());
-            Ok(())
+        methods.add_method("mutate", |lua, model, seed: Option<u64>| {
+            let mut model = model.borrow_mut();
+            let result = if let Some(seed) = seed {
+                model.try_mutate(&mut LuaRng::seed_from_u64(seed))
+            } else if let Some(mut rng) = lua.remove_app_data::<LuaRng>() {
+                // taken out of the app data while mutating so callbacks that touch the app data
+                // don't conflict with this borrow; they draw from it through their `random`
+                // argument instead
+                let result = model.try_mutate(&mut rng);
+                lua.set_app_data(rng);
+                result
+            } else {
+                model.try_mutate(&mut thread_rng())
+            };
+            result.map_err(LuaError::external)
         });
     }
 }
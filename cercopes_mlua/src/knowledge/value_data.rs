@@ -13,21 +13,40 @@ impl LuaFacetValueData {
         lua.create_userdata(self)
     }
 
-    pub fn borrow_mut(&self) -> Option<RefMut<FacetValueData<KnowledgeTypes>>> {
-        let model = self.model.borrow_mut();
-        if model.regarding.is_facet_relevant(&self.facet) {
-            Some(RefMut::map(model, |model| model.get_value_data(self.value.clone())))
+    pub fn borrow(&self) -> LuaResult<Option<Ref<'_, FacetValueData<KnowledgeTypes>>>> {
+        // initialize the value data before borrowing it immutably
+        if let Some(mut facet_data) = self.facet_data.borrow_mut()? {
+            facet_data.get_value_data(self.value.clone());
         } else {
-            None
+            return Ok(None);
         }
+        Ok(Some(Ref::map(self.model.borrow(), |model| {
+            &model.facets[&self.facet].values()[&self.value]
+        })))
+    }
+
+    pub fn update<F, R>(&self, update: F) -> LuaResult<Option<R>>
+    where
+        F: FnOnce(&mut FacetValueData<KnowledgeTypes>) -> R,
+    {
+        let regarding = self.model.borrow().regarding.clone();
+        if !regarding.try_is_facet_relevant(&self.facet).map_err(LuaError::external)? {
+            return Ok(None);
+        }
+        let value = self.value.clone();
+        self.model
+            .borrow_mut()
+            .try_update_facet(self.facet.clone(), |data| data.update_value(value, update))
+            .map(Some)
+            .map_err(LuaError::external)
     }
 }
 
 impl UserData for LuaFacetValueData {
     fn add_fields<'lua, F: UserDataFields<'lua, Self>>(fields: &mut F) {
         fields.add_field_method_get("total_strength", |_, value_data| {
-            if let Some(value_data) = value_data.borrow_mut() {
-                Ok(Some(value_data.total_strength))
+            if let Some(value_data) = value_data.borrow()? {
+                Ok(Some(value_data.total_strength()))
             } else {
                 Ok(None)
             }
@@ -42,28 +61,29 @@ impl UserData for LuaFacetValueData {
 
     fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
         methods.add_method("recompute_total_strength", |_, value_data, ()| {
-            if let Some(mut value_data) = value_data.borrow_mut() {
+            value_data.update(|value_data| {
                 value_data.recompute_total_strength();
-                Ok(Some(value_data.total_strength))
-            } else {
-                Ok(None)
-            }
+                value_data.total_strength()
+            })
         });
         methods.add_method("insert", |lua, value_data, (evidence, index): (_, Option<usize>)| {
-            let mut value_data_ref = if let Some(value_data) = value_data.borrow_mut() {
-                value_data
+            let index = value_data.update(|value_data| {
+                value_data.update_evidence(|all_evidence| {
+                    if let Some(orig_index) = index {
+                        let index = orig_index.checked_sub(1).unwrap();
+                        all_evidence.insert(index, EvidenceBuilder::into(evidence));
+                        orig_index
+                    } else {
+                        all_evidence.push(evidence.into());
+                        all_evidence.len()
+                    }
+                })
+            })?;
+            let index = if let Some(index) = index {
+                index
             } else {
                 return Ok(None);
             };
-            let index = if let Some(orig_index) = index {
-                let index = orig_index.checked_sub(1).unwrap();
-                value_data_ref.evidence.insert(index, EvidenceBuilder::into(evidence));
-                orig_index
-            } else {
-                let index = value_data_ref.evidence.len() + 1;
-                value_data_ref.evidence.push(evidence.into());
-                index
-            };
             LuaEvidence {
                 value_data: value_data.clone(),
                 index,
@@ -71,20 +91,22 @@ impl UserData for LuaFacetValueData {
             }.new(lua).map(Some)
         });
         methods.add_meta_method("__len", |_, value_data, ()| {
-            if let Some(value_data) = value_data.borrow_mut() {
-                Ok(Some(value_data.evidence.len()))
+            if let Some(value_data) = value_data.borrow()? {
+                Ok(Some(value_data.evidence().len()))
             } else {
                 Ok(None)
             }
         });
         methods.add_method("remove", |_, value_data, index: Option<usize>| {
-            if let Some(mut value_data) = value_data.borrow_mut() {
-                if let Some(index) = index {
-                    value_data.evidence.remove(index.checked_sub(1).unwrap());
-                } else {
-                    value_data.evidence.pop();
-                }
-            }
+            value_data.update(|value_data| {
+                value_data.update_evidence(|all_evidence| {
+                    if let Some(index) = index {
+                        all_evidence.remove(index.checked_sub(1).unwrap());
+                    } else {
+                        all_evidence.pop();
+                    }
+                })
+            })?;
 
             Ok(())
         });
@@ -97,8 +119,8 @@ impl UserData for LuaFacetValueData {
         });
         methods.add_function("iter_evidence", |lua, value_data: AnyUserData| {
             let iter_function = lua.create_function(|lua, (value_data, index)| {
-                if let Some(value_data) = LuaFacetValueData::borrow_mut(&value_data) {
-                    if index >= value_data.evidence.len() {
+                if let Some(value_data) = LuaFacetValueData::borrow(&value_data)? {
+                    if index >= value_data.evidence().len() {
                         return Nil.to_lua_multi(lua);
                     }
                 } else {
@@ -185,6 +207,16 @@ impl<'lua> ToLua<'lua> for EvidenceKindBuilder {
                 let previous: Box<EvidenceBuilder> = unsafe { std::mem::transmute(previous) };
                 table.set("previous", *previous)?;
             },
+            EvidenceKind::Custom(CustomKind {
+                name,
+                tag,
+                source,
+            }) => {
+                table.set("kind", "custom")?;
+                table.set("name", name)?;
+                table.set("tag", tag)?;
+                table.set("source", source)?;
+            },
         }
         table.to_lua(lua)
     }
@@ -241,6 +273,11 @@ impl<'lua> FromLua<'lua> for EvidenceKindBuilder {
             "mutation" => Ok(EvidenceKind::Mutation {
                 previous: Box::new(table.get::<_, EvidenceBuilder>("previous")?.into()),
             }.into()),
+            "custom" => Ok(EvidenceKind::Custom(CustomKind {
+                name: table.get("name")?,
+                tag: table.get::<_, Option<u32>>("tag")?.unwrap_or_default(),
+                source: table.get("source")?,
+            }).into()),
             kind => Err(LuaError::FromLuaConversionError {
                 from: "Value",
                 to: "EvidenceKindBuilder",
@@ -256,10 +293,11 @@ pub(super) struct EvidenceBuilder(Evidence<KnowledgeTypes>);
 
 impl<'lua> ToLua<'lua> for EvidenceBuilder {
     fn to_lua(self, lua: &'lua Lua) -> LuaResult<LuaValue<'lua>> {
-        let table = lua.create_table_with_capacity(0, 3)?;
+        let table = lua.create_table_with_capacity(0, 4)?;
         table.set("data", self.0.data)?;
         table.set("kind", EvidenceKindBuilder::from(self.0.kind))?;
         table.set("strength", self.0.strength)?;
+        table.set("weight", self.0.weight)?;
         table.to_lua(lua)
     }
 }
@@ -279,6 +317,8 @@ impl<'lua> FromLua<'lua> for EvidenceBuilder {
             data: table.get("data")?,
             kind: table.get::<_, EvidenceKindBuilder>("kind")?.into(),
             strength: table.get("strength")?,
+            weight: table.get::<_, Option<f32>>("weight")?.unwrap_or(1.0),
+            origin: None,
         }.into())
     }
 }
@@ -5,7 +5,7 @@ use std::ptr::NonNull;
 use mlua::{
     Result as LuaResult, Error as LuaError, ToLua, FromLua, UserData, Value as LuaValue,
     Table as LuaTable, String as LuaString, Lua, UserDataFields, UserDataMethods,
-    Nil, AnyUserData, MultiValue, ToLuaMulti, Function,
+    Nil, AnyUserData, MultiValue, ToLuaMulti, Function, Integer as LuaInteger,
     RegistryKey,
 };
 use ahash::AHashSet;
@@ -115,7 +115,7 @@ impl UserData for RegistryTableUD {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub(crate) struct RegistryTable(RegistryTableUD);
 
 impl RegistryTable {
@@ -20,36 +20,36 @@ local facets = {
 facets["hair color"].values.red = {
     facet = facets["hair color"].facet,
     hash_string = facets["hair color"].facet .. " red",
-    try_mutate = function(model_id, evidence_id)
+    try_mutate = function(value, holder, regarding, evidence_id, random)
         local options = {
             facets["hair color"].values.green,
             facets["hair color"].values.blue,
         }
-        return options[math.random(#options)]
+        return options[random(#options)]
     end,
 }
 
 facets["hair color"].values.green = {
     facet = facets["hair color"].facet,
     hash_string = facets["hair color"].facet .. " green",
-    try_mutate = function(model_id, evidence_id)
+    try_mutate = function(value, holder, regarding, evidence_id, random)
         local options = {
             facets["hair color"].values.red,
             facets["hair color"].values.blue,
         }
-        return options[math.random(#options)]
+        return options[random(#options)]
     end,
 }
 
 facets["hair color"].values.blue = {
     facet = facets["hair color"].facet,
     hash_string = facets["hair color"].facet .. " blue",
-    try_mutate = function(model_id, evidence_id)
+    try_mutate = function(value, holder, regarding, evidence_id, random)
         local options = {
             facets["hair color"].values.red,
             facets["hair color"].values.green,
         }
-        return options[math.random(#options)]
+        return options[random(#options)]
     end,
 }
 
@@ -92,4 +92,42 @@ entities.bob = {
 }
 
 local alice_of_bob = EvidenceModel(entities.alice, entities.bob)
-table.insert(entities.alice.models, alice_of_bob)
\ No newline at end of file
+table.insert(entities.alice.models, alice_of_bob)
+local hair_color = alice_of_bob[facets["hair color"].facet]
+hair_color[facets["hair color"].values.green]:insert({ data = {}, kind = "implantation", strength = 3 })
+hair_color[facets["hair color"].values.red]:insert({ data = {}, kind = "implantation", strength = 1 })
+assert(hair_color.strongest.hash_string == facets["hair color"].values.green.hash_string)
+assert(hair_color[facets["hair color"].values.green].total_strength == 3)
+hair_color[facets["hair color"].values.green]:recompute_total_strength()
+hair_color[facets["hair color"].values.red]:recompute_total_strength()
+local distribution = hair_color:distribution()
+assert(#distribution == 2)
+assert(distribution[1].value.hash_string == facets["hair color"].values.green.hash_string)
+assert(distribution[1].probability == 0.75)
+assert(hair_color:confidence() == 0.5)
+
+seed_rng(1)
+alice_of_bob:mutate()
+alice_of_bob:mutate(2)
+local total = 0
+for _, value in pairs(facets["hair color"].values) do
+    total = total + hair_color[value].total_strength
+end
+assert(total == 4)
+
+local function mutated(seed)
+    local model = EvidenceModel(entities.alice, entities.bob)
+    local red = model[facets["hair color"].facet][facets["hair color"].values.red]
+    for i = 1, 16 do
+        red:insert({ data = {}, kind = "implantation", strength = i })
+    end
+    model:mutate(seed)
+    return model[facets["hair color"].facet]
+end
+local first, second = mutated(3), mutated(3)
+for _, value in pairs(facets["hair color"].values) do
+    assert(#first[value] == #second[value])
+    assert(first[value].total_strength == second[value].total_strength)
+end
+assert(#first[facets["hair color"].values.green] > 0)
+assert(#first[facets["hair color"].values.blue] > 0)